@@ -0,0 +1,259 @@
+use std::{
+    future::poll_fn,
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_core::Stream;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+use crate::{
+    decoder::{DecodeFn, Decoded, Decoder, FinishFn},
+    error::Result,
+    writer::Encoder,
+    Dsv, Options,
+};
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// An asynchronous streaming reader over delimiter-separated values.
+///
+/// An `AsyncReader` parses records incrementally from any source implementing
+/// `AsyncRead` and yields them as a `Stream`. It shares its parser with
+/// `StreamReader`, so both accept the same input and produce the same records.
+pub struct AsyncReader<R> {
+    reader: R,
+    decoder: Decoder,
+    buffer: Box<[u8]>,
+    pos: usize,
+    len: usize,
+    eof: bool,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncReader<R> {
+    /// Create an asynchronous reader over a source.
+    pub fn new(reader: R) -> AsyncReader<R> {
+        AsyncReader::with_options(reader, Options::default())
+    }
+
+    /// Create an asynchronous reader over a source with options.
+    pub fn with_options(reader: R, options: Options) -> AsyncReader<R> {
+        AsyncReader {
+            reader,
+            decoder: Decoder::with_options(options),
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Read the next record, returning `None` at the end of the input.
+    pub async fn read_record(&mut self) -> Result<Option<Vec<String>>> {
+        poll_fn(|cx| self.poll_read_record(cx)).await
+    }
+
+    /// Read the next record, with `None` in place of fields that match one of
+    /// `Options::null_values`. Returns `None` at the end of the input.
+    pub async fn read_record_opt(&mut self) -> Result<Option<Vec<Option<String>>>> {
+        poll_fn(|cx| self.poll_read_record_opt(cx)).await
+    }
+
+    /// Attempt to read the next record, returning `None` at the end of the
+    /// input.
+    pub fn poll_read_record(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Vec<String>>>> {
+        self.poll_read_with(cx, Decoder::decode, Decoder::finish)
+    }
+
+    /// Attempt to read the next record, with `None` in place of fields that
+    /// match one of `Options::null_values`.
+    pub fn poll_read_record_opt(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<Vec<Option<String>>>>> {
+        self.poll_read_with(cx, Decoder::decode_opt, Decoder::finish_opt)
+    }
+
+    fn poll_read_with<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        decode: DecodeFn<T>,
+        finish: FinishFn<T>,
+    ) -> Poll<Result<Option<T>>> {
+        loop {
+            if self.pos == self.len {
+                if self.eof {
+                    return Poll::Ready(finish(&mut self.decoder));
+                }
+                let mut buf = ReadBuf::new(&mut self.buffer);
+                match ready!(Pin::new(&mut self.reader).poll_read(cx, &mut buf)) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Poll::Ready(Err(e.into())),
+                }
+                self.len = buf.filled().len();
+                self.pos = 0;
+                self.eof = self.len == 0;
+                continue;
+            }
+            let (consumed, decoded) = decode(&mut self.decoder, &self.buffer[self.pos..self.len])?;
+            self.pos += consumed;
+            if let Decoded::Record(record) = decoded {
+                return Poll::Ready(Ok(Some(record)));
+            }
+        }
+    }
+
+    /// Consume the reader, returning the underlying source.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for AsyncReader<R> {
+    type Item = Result<Vec<String>>;
+
+    /// Returns the next record in the input.
+    ///
+    /// The stream ends after the first error.
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.poll_read_record(cx)) {
+            Ok(Some(record)) => Poll::Ready(Some(Ok(record))),
+            Ok(None) => {
+                this.done = true;
+                Poll::Ready(None)
+            }
+            Err(e) => {
+                this.done = true;
+                Poll::Ready(Some(Err(e)))
+            }
+        }
+    }
+}
+
+/// An asynchronous writer of delimiter-separated values.
+///
+/// An `AsyncWriter` shares its formatting with `Writer`, so both produce the
+/// same output.
+pub struct AsyncWriter<W> {
+    writer: W,
+    encoder: Encoder,
+    buffer: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
+    /// Create an asynchronous writer over a destination.
+    pub fn new(writer: W) -> AsyncWriter<W> {
+        AsyncWriter::with_options(writer, Options::default())
+    }
+
+    /// Create an asynchronous writer over a destination with options.
+    pub fn with_options(writer: W, options: Options) -> AsyncWriter<W> {
+        AsyncWriter {
+            writer,
+            encoder: Encoder::new(options),
+            buffer: vec![],
+        }
+    }
+
+    /// Write a record.
+    pub async fn write_record<I, T>(&mut self, record: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.buffer.clear();
+        self.encoder.encode(record, &mut self.buffer)?;
+        self.writer.write_all(&self.buffer).await?;
+        Ok(())
+    }
+
+    /// Write the headers, if any, and every record in a table.
+    pub async fn write_dsv(&mut self, dsv: &Dsv) -> Result<()> {
+        if let Some(headers) = dsv.headers() {
+            self.write_record(headers).await?;
+        }
+        for record in dsv.iter() {
+            self.buffer.clear();
+            self.encoder
+                .encode_opt(record.fields_opt(), &mut self.buffer)?;
+            self.writer.write_all(&self.buffer).await?;
+        }
+        Ok(())
+    }
+
+    /// Flush the underlying destination.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Consume the writer, returning the underlying destination.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StreamReader;
+
+    #[tokio::test]
+    async fn matches_stream_reader() -> Result<()> {
+        let text = "foo,\"bar\nbaz\"\n,qux\n\"a\"\"b\",c";
+        let mut reader = AsyncReader::new(text.as_bytes());
+        let mut records = vec![];
+        while let Some(record) = reader.read_record().await? {
+            records.push(record);
+        }
+        let expected: Vec<_> = StreamReader::new(text.as_bytes()).collect::<Result<_>>()?;
+        assert_eq!(records, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn null_values() -> Result<()> {
+        let options = Options {
+            null_values: vec!["\\N".into()],
+            ..Default::default()
+        };
+        let text = "\\N,\"\\N\"\n";
+        let mut reader = AsyncReader::with_options(text.as_bytes(), options);
+        let record = reader.read_record_opt().await?.unwrap();
+        assert_eq!(record, [None, Some("\\N".into())]);
+        assert_eq!(reader.read_record_opt().await?, None);
+        Ok(())
+    }
+
+    async fn next<R: AsyncRead + Unpin>(
+        reader: &mut AsyncReader<R>,
+    ) -> Option<Result<Vec<String>>> {
+        poll_fn(|cx| Pin::new(&mut *reader).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn stream() -> Result<()> {
+        let mut reader = AsyncReader::new(&b"foo\nbar,baz"[..]);
+        assert_eq!(next(&mut reader).await.unwrap()?, vec!["foo"]);
+        assert!(next(&mut reader).await.unwrap().is_err());
+        assert!(next(&mut reader).await.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write() -> Result<()> {
+        let mut writer = AsyncWriter::new(vec![]);
+        writer.write_record(["foo", "bar,baz"]).await?;
+        writer.flush().await?;
+        assert_eq!(writer.into_inner(), b"foo,\"bar,baz\"\n");
+        Ok(())
+    }
+}
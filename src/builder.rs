@@ -0,0 +1,146 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{error::Result, Dsv};
+
+/// A builder for constructing a `Dsv` programmatically.
+///
+/// Fields can be anything that implements `ToString`. The widths of the
+/// headers and records are validated when the table is built.
+///
+/// ```
+/// use dsv::DsvBuilder;
+///
+/// let dsv = DsvBuilder::new()
+///     .headers(["fruit", "count"])
+///     .record(["apples", "2"])
+///     .record(vec!["oranges".to_string(), 3.to_string()])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(dsv[1][1], "3");
+/// ```
+#[derive(Default)]
+pub struct DsvBuilder {
+    headers: Option<Vec<String>>,
+    records: Vec<Vec<String>>,
+}
+
+impl DsvBuilder {
+    /// Create a new builder.
+    pub fn new() -> DsvBuilder {
+        DsvBuilder {
+            ..Default::default()
+        }
+    }
+
+    /// Set the headers of the table.
+    pub fn headers<I, T>(mut self, headers: I) -> DsvBuilder
+    where
+        I: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        self.headers = Some(headers.into_iter().map(|h| h.to_string()).collect());
+        self
+    }
+
+    /// Append a record to the table.
+    pub fn record<I, T>(mut self, record: I) -> DsvBuilder
+    where
+        I: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        self.records
+            .push(record.into_iter().map(|f| f.to_string()).collect());
+        self
+    }
+
+    /// Append several records to the table.
+    pub fn records<I, R, T>(mut self, records: I) -> DsvBuilder
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        for record in records {
+            self = self.record(record);
+        }
+        self
+    }
+
+    /// Build the table.
+    ///
+    /// Fails if the headers and records do not all contain the same number of
+    /// fields.
+    pub fn build(self) -> Result<Dsv> {
+        let mut dsv = Dsv::new();
+        dsv.headers = self.headers;
+        for record in self.records {
+            dsv.push_record(record)?;
+        }
+        Ok(dsv)
+    }
+}
+
+impl Dsv {
+    /// Create a table from records of anything that implements `ToString`.
+    ///
+    /// Fails if the records do not all contain the same number of fields.
+    pub fn from_records<I, R, T>(records: I) -> Result<Dsv>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        DsvBuilder::new().records(records).build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Options};
+
+    #[test]
+    fn build() -> Result<()> {
+        let dsv = DsvBuilder::new()
+            .headers(["name", "count"])
+            .record(["a", "1"])
+            .records([[String::from("b"), 2.to_string()]])
+            .build()?;
+        let options = Options {
+            headers: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            dsv,
+            Dsv::from_str_with_options("name,count\na,1\nb,2", options)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mismatched_headers() {
+        let dsv = DsvBuilder::new()
+            .headers(["name", "count"])
+            .record(["a"])
+            .build();
+        assert!(matches!(
+            dsv,
+            Err(Error::FieldCount {
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn from_records() -> Result<()> {
+        let dsv = Dsv::from_records([[1, 2], [3, 4]])?;
+        assert_eq!(dsv, Dsv::from_str("1,2\n3,4")?);
+        assert!(Dsv::from_records(vec![vec![1, 2], vec![3]]).is_err());
+        Ok(())
+    }
+}
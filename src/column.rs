@@ -0,0 +1,113 @@
+use alloc::{string::String, vec::Vec};
+use core::{ops::Range, slice};
+
+use crate::{
+    error::{Error, Result},
+    Dsv,
+};
+
+/// A reference to a column, either by index or by header name.
+///
+/// Most methods that take a column accept either a `usize` or a `&str`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Column<'a> {
+    Index(usize),
+    Name(&'a str),
+}
+
+impl Column<'_> {
+    /// Resolve the column to an index, given the headers and width of a table.
+    pub(crate) fn resolve(&self, headers: Option<&[String]>, width: usize) -> Result<usize> {
+        match *self {
+            Column::Index(index) if index < width => Ok(index),
+            Column::Index(_) => Err(Error::OutOfBounds),
+            Column::Name(name) => headers
+                .and_then(|headers| headers.iter().position(|h| h == name))
+                .ok_or_else(|| Error::UnknownColumn(name.into())),
+        }
+    }
+}
+
+impl From<usize> for Column<'_> {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+impl<'a> From<&'a str> for Column<'a> {
+    fn from(name: &'a str) -> Self {
+        Column::Name(name)
+    }
+}
+
+impl<'a> From<&'a String> for Column<'a> {
+    fn from(name: &'a String) -> Self {
+        Column::Name(name)
+    }
+}
+
+/// An iterator over the fields in one column of a table.
+///
+/// Created by `Dsv::column`.
+pub struct Fields<'a> {
+    records: slice::Iter<'a, Vec<String>>,
+    column: usize,
+}
+
+impl<'a> Fields<'a> {
+    pub(crate) fn new(dsv: &'a Dsv, column: usize) -> Fields<'a> {
+        Fields {
+            records: dsv.records.iter(),
+            column,
+        }
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.records
+            .next()
+            .map(|record| record[self.column].as_str())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.records.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Fields<'_> {}
+
+/// An iterator over the columns of a table.
+///
+/// Created by `Dsv::columns`.
+pub struct Columns<'a> {
+    dsv: &'a Dsv,
+    columns: Range<usize>,
+}
+
+impl<'a> Columns<'a> {
+    pub(crate) fn new(dsv: &'a Dsv) -> Columns<'a> {
+        Columns {
+            dsv,
+            columns: 0..dsv.width(),
+        }
+    }
+}
+
+impl<'a> Iterator for Columns<'a> {
+    type Item = Fields<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.columns
+            .next()
+            .map(|column| Fields::new(self.dsv, column))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.columns.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Columns<'_> {}
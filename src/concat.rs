@@ -0,0 +1,190 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::{
+    error::{Error, Result},
+    Dsv,
+};
+
+/// How to handle columns that are not in every table when concatenating.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Align {
+    /// Fill missing columns with empty fields.
+    #[default]
+    Fill,
+    /// Fail unless every table has the same columns.
+    Exact,
+}
+
+impl Dsv {
+    /// Concatenate the records of several tables, filling in missing columns.
+    ///
+    /// See `Dsv::concat_with` for details.
+    pub fn concat<'a, I: IntoIterator<Item = &'a Dsv>>(tables: I) -> Result<Dsv> {
+        Dsv::concat_with(tables, Align::Fill)
+    }
+
+    /// Concatenate the records of several tables.
+    ///
+    /// If the tables have headers, columns are matched by name, in the order
+    /// in which the names first appear. Columns that are not in every table
+    /// are either filled with empty fields or, with `Align::Exact`, rejected
+    /// with `Error::UnknownColumn`. Tables without headers are matched by
+    /// position and must have the same width. Either every table or none must
+    /// have headers.
+    pub fn concat_with<'a, I: IntoIterator<Item = &'a Dsv>>(
+        tables: I,
+        align: Align,
+    ) -> Result<Dsv> {
+        let tables: Vec<&Dsv> = tables.into_iter().collect();
+        let Some(first) = tables.first() else {
+            return Ok(Dsv::new());
+        };
+        if tables
+            .iter()
+            .any(|t| t.headers.is_some() != first.headers.is_some())
+        {
+            return Err(Error::Headers);
+        }
+
+        let Some(first_headers) = first.headers() else {
+            let mut dsv = Dsv::new();
+            for table in &tables {
+                for ix in 0..table.records.len() {
+                    dsv.push_record_opt(table.record_opt(ix))?;
+                }
+            }
+            return Ok(dsv);
+        };
+
+        let mut headers: Vec<String> = first_headers.to_vec();
+        for table in &tables[1..] {
+            for header in table.headers().unwrap() {
+                if !headers.contains(header) {
+                    if align == Align::Exact {
+                        return Err(Error::UnknownColumn(header.clone()));
+                    }
+                    headers.push(header.clone());
+                }
+            }
+        }
+
+        let mut records = vec![];
+        for table in &tables {
+            let table_headers = table.headers().unwrap();
+            let positions = headers
+                .iter()
+                .map(|header| {
+                    let position = table_headers.iter().position(|h| h == header);
+                    match position {
+                        None if align == Align::Exact => Err(Error::UnknownColumn(header.clone())),
+                        position => Ok(position),
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            for ix in 0..table.records.len() {
+                let aligned = positions
+                    .iter()
+                    .map(|position| match position {
+                        Some(p) => table.field_opt(ix, *p).map(String::from),
+                        None => Some(String::new()),
+                    })
+                    .collect();
+                records.push(aligned);
+            }
+        }
+
+        Ok(Dsv::from_opt(Some(headers), records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsvBuilder, Options};
+
+    fn january() -> Dsv {
+        DsvBuilder::new()
+            .headers(["fruit", "count"])
+            .record(["apples", "2"])
+            .build()
+            .unwrap()
+    }
+
+    fn february() -> Dsv {
+        DsvBuilder::new()
+            .headers(["count", "price", "fruit"])
+            .record(["3", "1.5", "oranges"])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn fill() -> Result<()> {
+        let dsv = Dsv::concat(&[january(), february()])?;
+        assert_eq!(dsv.headers().unwrap(), ["fruit", "count", "price"]);
+        assert_eq!(
+            dsv.records(),
+            [["apples", "2", ""], ["oranges", "3", "1.5"]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn exact() -> Result<()> {
+        let result = Dsv::concat_with(&[january(), february()], Align::Exact);
+        assert!(matches!(result, Err(Error::UnknownColumn(name)) if name == "price"));
+        let result = Dsv::concat_with(&[february(), january()], Align::Exact);
+        assert!(matches!(result, Err(Error::UnknownColumn(name)) if name == "price"));
+
+        let reordered = DsvBuilder::new()
+            .headers(["count", "fruit"])
+            .record(["4", "pears"])
+            .build()?;
+        let dsv = Dsv::concat_with([&january(), &reordered], Align::Exact)?;
+        assert_eq!(dsv[1], ["pears", "4"]);
+        Ok(())
+    }
+
+    #[test]
+    fn without_headers() -> Result<()> {
+        let a = Dsv::from_str("a,1")?;
+        let b = Dsv::from_str("b,2")?;
+        let dsv = Dsv::concat([&a, &b])?;
+        assert_eq!(dsv.records(), [["a", "1"], ["b", "2"]]);
+
+        let c = Dsv::from_str("c")?;
+        assert!(matches!(
+            Dsv::concat([&a, &c]),
+            Err(Error::FieldCount { .. })
+        ));
+        assert!(matches!(Dsv::concat([&a, &january()]), Err(Error::Headers)));
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let read = |text, headers| {
+            let options = Options {
+                headers,
+                null_values: vec!["NA".into()],
+                ..Default::default()
+            };
+            Dsv::from_str_with_options(text, options)
+        };
+        let a = read("fruit,count\napples,NA", true)?;
+        let b = read("count,fruit\n3,NA", true)?;
+        let expected = read("fruit,count\napples,NA\nNA,3", true)?;
+        assert_eq!(Dsv::concat([&a, &b])?, expected);
+
+        let a = read("a,NA", false)?;
+        let b = read("NA,b", false)?;
+        assert_eq!(Dsv::concat([&a, &b])?, read("a,NA\nNA,b", false)?);
+        Ok(())
+    }
+
+    #[test]
+    fn empty() -> Result<()> {
+        assert_eq!(Dsv::concat([])?, Dsv::new());
+        Ok(())
+    }
+}
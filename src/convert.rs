@@ -0,0 +1,359 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// A boolean parsed from `true`/`false`, `yes`/`no`, or `1`/`0`.
+///
+/// Case and surrounding whitespace are ignored.
+///
+/// ```
+/// use dsv::{Bool, Dsv};
+///
+/// let dsv = Dsv::from_str("Yes\nno\n1").unwrap();
+/// let flags: Vec<Bool> = dsv.parse_column(0).unwrap();
+/// assert_eq!(flags, [Bool(true), Bool(false), Bool(true)]);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Bool(pub bool);
+
+impl FromStr for Bool {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Bool> {
+        let s = s.trim();
+        let is = |value: &str| s.eq_ignore_ascii_case(value);
+        if is("true") || is("yes") || s == "1" {
+            Ok(Bool(true))
+        } else if is("false") || is("no") || s == "0" {
+            Ok(Bool(false))
+        } else {
+            Err(Error::Parse)
+        }
+    }
+}
+
+impl From<Bool> for bool {
+    fn from(value: Bool) -> bool {
+        value.0
+    }
+}
+
+/// A number whose integer part may be grouped in thousands with commas, such
+/// as `-1,234,567.89`.
+///
+/// This is the notation of `NumberFormat::new().grouping(',')`. Groups must be
+/// complete, so `1,23` is rejected, and commas after the integer part, as in
+/// `1.234,56`, are rejected.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Thousands(pub f64);
+
+impl FromStr for Thousands {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Thousands> {
+        NumberFormat::new()
+            .grouping(',')
+            .parse_f64(s)
+            .map(Thousands)
+    }
+}
+
+impl From<Thousands> for f64 {
+    fn from(value: Thousands) -> f64 {
+        value.0
+    }
+}
+
+/// A percentage such as `12.5%`, parsed as a fraction such as `0.125`.
+///
+/// This is the notation of `NumberFormat::new().percent("%")`, except that the
+/// percent symbol is required.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Percent(pub f64);
+
+impl FromStr for Percent {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Percent> {
+        NumberFormat::new()
+            .percent("%")
+            .normalize(s)
+            .filter(|normalized| normalized.percent)
+            .ok_or(Error::Parse)?
+            .to_f64()
+            .map(Percent)
+    }
+}
+
+impl From<Percent> for f64 {
+    fn from(value: Percent) -> f64 {
+        value.0
+    }
+}
+
+/// The notation of numbers written for a locale, such as `1.234,56 €`.
+///
+/// The default format is the notation that Rust itself uses. Signs may come
+/// before or after a currency symbol, and surrounding whitespace is ignored.
+///
+/// ```
+/// use dsv::NumberFormat;
+///
+/// let format = NumberFormat::new().decimal(',').grouping('.').currency("€");
+/// assert_eq!(format.parse_f64("-1.234,56 €").unwrap(), -1234.56);
+/// assert_eq!(format.parse_i64("1.000").unwrap(), 1000);
+/// assert!(format.parse_f64("1.23,4").is_err());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct NumberFormat {
+    /// The character that separates the integer and fractional parts.
+    ///
+    /// Default: `.`.
+    pub decimal: char,
+    /// The character that groups the digits of the integer part in thousands,
+    /// if any. Groups must be complete, so `1,23` is rejected.
+    ///
+    /// Default: `None`.
+    pub grouping: Option<char>,
+    /// Symbols that may come before or after a number, such as `$` or `EUR`.
+    ///
+    /// Default: empty.
+    pub currency: Vec<String>,
+    /// A symbol that may follow a number to divide it by 100, such as `%`.
+    ///
+    /// Default: `None`.
+    pub percent: Option<String>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal: '.',
+            grouping: None,
+            currency: vec![],
+            percent: None,
+        }
+    }
+}
+
+/// A number in Rust's notation, as normalized from a `NumberFormat`.
+struct Normalized {
+    number: String,
+    integer: bool,
+    percent: bool,
+}
+
+impl Normalized {
+    fn to_f64(&self) -> Result<f64> {
+        let number: f64 = self.number.parse().map_err(|_| Error::Parse)?;
+        Ok(if self.percent { number / 100.0 } else { number })
+    }
+}
+
+impl NumberFormat {
+    /// Create the default format.
+    pub fn new() -> NumberFormat {
+        NumberFormat::default()
+    }
+
+    /// Set the decimal separator.
+    pub fn decimal(mut self, decimal: char) -> NumberFormat {
+        self.decimal = decimal;
+        self
+    }
+
+    /// Set the grouping separator.
+    pub fn grouping(mut self, grouping: char) -> NumberFormat {
+        self.grouping = Some(grouping);
+        self
+    }
+
+    /// Allow a currency symbol.
+    pub fn currency<S: Into<String>>(mut self, symbol: S) -> NumberFormat {
+        self.currency.push(symbol.into());
+        self
+    }
+
+    /// Set the percent symbol.
+    pub fn percent<S: Into<String>>(mut self, symbol: S) -> NumberFormat {
+        self.percent = Some(symbol.into());
+        self
+    }
+
+    /// Parse a number.
+    pub fn parse_f64(&self, s: &str) -> Result<f64> {
+        self.normalize(s).ok_or(Error::Parse)?.to_f64()
+    }
+
+    /// Parse an integer, which must not have a fractional part, exponent, or
+    /// percent symbol.
+    pub fn parse_i64(&self, s: &str) -> Result<i64> {
+        match self.normalize(s) {
+            Some(normalized) if normalized.integer && !normalized.percent => {
+                normalized.number.parse().map_err(|_| Error::Parse)
+            }
+            _ => Err(Error::Parse),
+        }
+    }
+
+    fn normalize(&self, s: &str) -> Option<Normalized> {
+        let mut s = s.trim();
+        let mut negative = sign(&mut s);
+        for symbol in &self.currency {
+            if let Some(rest) = s.strip_prefix(symbol.as_str()) {
+                s = rest.trim_start();
+                break;
+            }
+            if let Some(rest) = s.strip_suffix(symbol.as_str()) {
+                s = rest.trim_end();
+                break;
+            }
+        }
+        if negative.is_none() {
+            negative = sign(&mut s);
+        }
+        let percent = match self.percent.as_deref().and_then(|p| s.strip_suffix(p)) {
+            Some(rest) => {
+                s = rest.trim_end();
+                true
+            }
+            None => false,
+        };
+
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(ix) => (&s[..ix], Some(&s[ix + 1..])),
+            None => (s, None),
+        };
+        let (integer, fraction) = match mantissa.split_once(self.decimal) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (mantissa, None),
+        };
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        let integer: String = match self.grouping {
+            Some(grouping) if integer.contains(grouping) => {
+                let mut groups = integer.split(grouping);
+                let first = groups.next().unwrap_or("");
+                let complete =
+                    (1..=3).contains(&first.len()) && groups.clone().all(|group| group.len() == 3);
+                if !complete {
+                    return None;
+                }
+                core::iter::once(first).chain(groups).collect()
+            }
+            _ => integer.into(),
+        };
+        let fraction = fraction.unwrap_or("");
+        if !is_digits(&integer) || !is_digits(fraction) || integer.len() + fraction.len() == 0 {
+            return None;
+        }
+
+        let mut number = String::new();
+        if negative == Some(true) {
+            number.push('-');
+        }
+        number.push_str(&integer);
+        if !fraction.is_empty() {
+            number.push('.');
+            number.push_str(fraction);
+        }
+        if let Some(exponent) = exponent {
+            let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            if digits.is_empty() || !is_digits(digits) {
+                return None;
+            }
+            number.push('e');
+            number.push_str(exponent);
+        }
+        Some(Normalized {
+            number,
+            integer: !mantissa.contains(self.decimal) && exponent.is_none(),
+            percent,
+        })
+    }
+}
+
+/// Take a leading sign, returning whether it is negative.
+fn sign(s: &mut &str) -> Option<bool> {
+    let negative = s.starts_with('-');
+    let rest = s.strip_prefix(['-', '+'])?;
+    *s = rest.trim_start();
+    Some(negative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bool() {
+        for value in ["true", "TRUE", "yes", " Yes ", "1"] {
+            assert_eq!(value.parse::<Bool>().unwrap(), Bool(true), "{value}");
+        }
+        for value in ["false", "No", "0"] {
+            assert_eq!(value.parse::<Bool>().unwrap(), Bool(false), "{value}");
+        }
+        for value in ["", "y", "2", "truthy"] {
+            assert!(value.parse::<Bool>().is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn thousands() {
+        let parse = |s: &str| s.parse::<Thousands>().map(f64::from).ok();
+        assert_eq!(parse("1,234,567.89"), Some(1234567.89));
+        assert_eq!(parse("-12,345"), Some(-12345.0));
+        assert_eq!(parse("999"), Some(999.0));
+        assert_eq!(parse("1,23"), None);
+        assert_eq!(parse("1234,567"), None);
+        assert_eq!(parse(",123"), None);
+        assert_eq!(parse("1,,234"), None);
+        assert_eq!(parse("1.234,56"), None);
+        assert_eq!(parse("1.5,000"), None);
+        assert_eq!(parse("12,345.6,7"), None);
+        assert_eq!(parse("1e1,000"), None);
+    }
+
+    #[test]
+    fn number_format() {
+        let format = NumberFormat::new()
+            .decimal(',')
+            .grouping('.')
+            .currency("€")
+            .currency("EUR")
+            .percent("%");
+        let parse = |s: &str| format.parse_f64(s).ok();
+        assert_eq!(parse("1.234.567,89"), Some(1234567.89));
+        assert_eq!(parse("-1.234,5 €"), Some(-1234.5));
+        assert_eq!(parse("€ -12"), Some(-12.0));
+        assert_eq!(parse("EUR 1,5e3"), Some(1500.0));
+        assert_eq!(parse("12,5 %"), Some(0.125));
+        assert_eq!(parse(",5"), Some(0.5));
+        assert_eq!(parse("1.23"), None);
+        assert_eq!(parse("1,2,3"), None);
+        assert_eq!(parse("1.5"), None);
+        assert_eq!(parse("$5"), None);
+        assert_eq!(parse("€"), None);
+        assert_eq!(parse("1e"), None);
+
+        assert_eq!(format.parse_i64("-1.000 €").ok(), Some(-1000));
+        assert_eq!(format.parse_i64("1,0").ok(), None);
+        assert_eq!(format.parse_i64("10%").ok(), None);
+
+        let plain = NumberFormat::new();
+        assert_eq!(plain.parse_f64("-4.5e-1").ok(), Some(-0.45));
+        assert_eq!(plain.parse_f64("5.").ok(), Some(5.0));
+        assert_eq!(plain.parse_i64("+42").ok(), Some(42));
+        assert_eq!(plain.parse_f64("1,000").ok(), None);
+        assert_eq!(plain.parse_f64("12%").ok(), None);
+    }
+
+    #[test]
+    fn percent() {
+        let parse = |s: &str| s.parse::<Percent>().map(f64::from).ok();
+        assert_eq!(parse("12.5%"), Some(0.125));
+        assert_eq!(parse("-50 %"), Some(-0.5));
+        assert_eq!(parse("12.5"), None);
+        assert_eq!(parse("%"), None);
+    }
+}
@@ -0,0 +1,714 @@
+use alloc::format;
+use core::{fmt, str::FromStr};
+
+use crate::error::{Error, Result};
+
+/// Formats tried, in order, when detecting the format of a value.
+///
+/// Day-first formats are tried before month-first ones, so a value such as
+/// `05/07/2023` is read as the 5th of July. Use `DateTime::detect_format` to
+/// choose one format for a whole column instead.
+const FORMATS: [&str; 16] = [
+    "%+",
+    "%d/%m/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%d/%m/%Y",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d.%m.%Y",
+    "%d %b %Y",
+    "%b %d, %Y",
+    "%Y%m%d",
+    "%s",
+];
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// A date and time of day, with an optional UTC offset.
+///
+/// Values are parsed with a strftime-like format using
+/// `DateTime::parse_with_format`, or with an automatically detected format
+/// using `FromStr`, which also makes `DateTime` usable with `Record::get_as`
+/// and `Dsv::parse_column`. Years are limited to four digits.
+///
+/// ```
+/// use dsv::{DateTime, Dsv};
+///
+/// let dsv = Dsv::from_str("2023-07-18\n18/07/2023 14:05\n1689689100").unwrap();
+/// let times: Vec<DateTime> = dsv.parse_column(0).unwrap();
+/// assert_eq!((times[1].day, times[1].month, times[1].hour), (18, 7, 14));
+/// assert_eq!(times[2].to_string(), "2023-07-18T14:05:00Z");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    /// The second, which is 60 during a leap second.
+    pub second: u32,
+    pub nanosecond: u32,
+    /// The offset from UTC in seconds, if the value specifies one.
+    pub offset: Option<i32>,
+}
+
+impl Default for DateTime {
+    /// The start of 1970, without an offset.
+    fn default() -> Self {
+        Self {
+            year: 1970,
+            month: 1,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+            offset: None,
+        }
+    }
+}
+
+impl DateTime {
+    /// Get the date and time at a Unix timestamp, in UTC.
+    ///
+    /// Returns `None` if the year is outside of the range 0 to 9999.
+    pub fn from_timestamp(seconds: i64) -> Option<DateTime> {
+        let days = seconds.div_euclid(86_400);
+        let time = seconds.rem_euclid(86_400) as u32;
+        let (year, month, day) = civil_from_days(days);
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        Some(DateTime {
+            year: year as i32,
+            month,
+            day,
+            hour: time / 3600,
+            minute: time / 60 % 60,
+            second: time % 60,
+            nanosecond: 0,
+            offset: Some(0),
+        })
+    }
+
+    /// Get the Unix timestamp of this date and time.
+    ///
+    /// Values without an offset are treated as UTC.
+    pub fn timestamp(&self) -> i64 {
+        let days = days_from_civil(self.year as i64, self.month, self.day);
+        let time = self.hour * 3600 + self.minute * 60 + self.second;
+        days * 86_400 + time as i64 - self.offset.unwrap_or(0) as i64
+    }
+
+    /// Parse a value with a strftime-like format.
+    ///
+    /// The format supports the following specifiers. Other characters must
+    /// match exactly.
+    ///
+    /// * `%Y`: four-digit year; `%y`: two-digit year, 1969 to 2068
+    /// * `%m`: month; `%b` or `%B`: month name, full or abbreviated
+    /// * `%d` or `%e`: day of the month
+    /// * `%H`: hour; `%I`: hour on a 12-hour clock; `%p`: `AM` or `PM`
+    /// * `%M`: minute; `%S`: second; `%f`: fractional seconds
+    /// * `%z`: UTC offset, such as `Z`, `+01:00`, or `-0130`
+    /// * `%F`: same as `%Y-%m-%d`; `%T`: same as `%H:%M:%S`
+    /// * `%+`: an ISO 8601 date, optionally with a time and offset
+    /// * `%s`: a Unix timestamp in seconds
+    /// * `%%`: a literal `%`
+    ///
+    /// Numbers other than years may omit leading zeros. Components that are
+    /// not in the format default to the start of 1970.
+    ///
+    /// Fails with `Error::Expression` if the format is not valid, or with
+    /// `Error::Parse` if the value does not match it.
+    pub fn parse_with_format(value: &str, format: &str) -> Result<DateTime> {
+        Format::new(format)?.parse(value).ok_or(Error::Parse)
+    }
+
+    /// Find a format that every value matches, for parsing them consistently.
+    ///
+    /// The formats tried are the ones used by `FromStr`. Empty values are
+    /// ignored. Returns `None` if no format matches every value.
+    ///
+    /// ```
+    /// use dsv::DateTime;
+    ///
+    /// let format = DateTime::detect_format(["05/07/2023", "07/18/2023"]);
+    /// assert_eq!(format, Some("%m/%d/%Y"));
+    /// ```
+    pub fn detect_format<'a, I>(values: I) -> Option<&'static str>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut matches = [true; FORMATS.len()];
+        for value in values.into_iter().filter(|value| !value.is_empty()) {
+            for (matches, format) in matches.iter_mut().zip(FORMATS) {
+                *matches = *matches && Format(format).parse(value).is_some();
+            }
+        }
+        FORMATS
+            .iter()
+            .zip(matches)
+            .find_map(|(&format, matches)| matches.then_some(format))
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = Error;
+
+    /// Parse a value in the first of several common formats that it matches,
+    /// including ISO 8601, `DD/MM/YYYY HH:MM`, and Unix timestamps.
+    fn from_str(s: &str) -> Result<DateTime> {
+        let s = s.trim();
+        FORMATS
+            .iter()
+            .find_map(|format| Format(format).parse(s))
+            .ok_or(Error::Parse)
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// Formats the value as ISO 8601.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if self.nanosecond > 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        match self.offset {
+            None => Ok(()),
+            Some(0) => write!(f, "Z"),
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let offset = offset.unsigned_abs() / 60;
+                write!(f, "{sign}{:02}:{:02}", offset / 60, offset % 60)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::NaiveDateTime {
+    type Error = Error;
+
+    /// Converts the value as written, ignoring its offset.
+    ///
+    /// Fails with `Error::Unrepresentable` if a field is out of range, which
+    /// is never the case for parsed values.
+    fn try_from(value: DateTime) -> Result<Self> {
+        // a leap second is represented by an extra second of nanoseconds
+        let (second, nanosecond) = match value.second {
+            60 => (59, value.nanosecond.checked_add(1_000_000_000)),
+            second => (second, Some(value.nanosecond)),
+        };
+        chrono::NaiveDate::from_ymd_opt(value.year, value.month, value.day)
+            .zip(nanosecond)
+            .and_then(|(date, nanosecond)| {
+                date.and_hms_nano_opt(value.hour, value.minute, second, nanosecond)
+            })
+            .ok_or(Error::Unrepresentable)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::DateTime<chrono::FixedOffset> {
+    type Error = Error;
+
+    /// Converts the value at its offset, treating values without one as UTC.
+    ///
+    /// Fails with `Error::Unrepresentable` if a field or the offset is out of
+    /// range, which is never the case for parsed values.
+    fn try_from(value: DateTime) -> Result<Self> {
+        let offset = chrono::FixedOffset::east_opt(value.offset.unwrap_or(0))
+            .ok_or(Error::Unrepresentable)?;
+        let local = chrono::NaiveDateTime::try_from(value)?;
+        chrono::TimeZone::from_local_datetime(&offset, &local)
+            .single()
+            .ok_or(Error::Unrepresentable)
+    }
+}
+
+/// The specifiers supported in formats.
+const SPECIFIERS: &str = "YymbBhdeHIpPMSfz+sFT%";
+
+/// A validated format string.
+pub(crate) struct Format<'a>(&'a str);
+
+impl<'a> Format<'a> {
+    pub fn new(format: &'a str) -> Result<Format<'a>> {
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                continue;
+            }
+            match chars.next() {
+                Some(specifier) if SPECIFIERS.contains(specifier) => {}
+                Some(specifier) => {
+                    let message = format!("unknown format specifier `%{specifier}`");
+                    return Err(Error::Expression(message));
+                }
+                None => return Err(Error::Expression("format ends with `%`".into())),
+            }
+        }
+        Ok(Format(format))
+    }
+
+    /// Parse a value, returning `None` if it does not match the format or is
+    /// not a valid date and time.
+    pub fn parse(&self, value: &str) -> Option<DateTime> {
+        let mut parsed = Parsed {
+            dt: DateTime::default(),
+            short_hour: None,
+            pm: false,
+        };
+        let rest = parsed.parse(self.0, value)?;
+        if !rest.is_empty() {
+            return None;
+        }
+
+        let mut dt = parsed.dt;
+        if let Some(hour) = parsed.short_hour {
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            dt.hour = hour % 12 + if parsed.pm { 12 } else { 0 };
+        }
+        let valid = (1..=12).contains(&dt.month)
+            && (1..=days_in_month(dt.year, dt.month)).contains(&dt.day)
+            && dt.hour <= 23
+            && dt.minute <= 59
+            && dt.second <= 60;
+        valid.then_some(dt)
+    }
+}
+
+/// The components parsed from a value so far.
+struct Parsed {
+    dt: DateTime,
+    /// The hour on a 12-hour clock.
+    short_hour: Option<u32>,
+    pm: bool,
+}
+
+impl Parsed {
+    /// Parse the start of a value with a format, returning the rest.
+    fn parse<'v>(&mut self, format: &str, mut s: &'v str) -> Option<&'v str> {
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                s = s.strip_prefix(c)?;
+                continue;
+            }
+            match chars.next()? {
+                'Y' => self.dt.year = number(&mut s, 4, 4)? as i32,
+                'y' => {
+                    let year = number(&mut s, 2, 2)? as i32;
+                    self.dt.year = if year < 69 { 2000 + year } else { 1900 + year };
+                }
+                'm' => self.dt.month = number(&mut s, 1, 2)?,
+                'b' | 'B' | 'h' => self.dt.month = month_name(&mut s)?,
+                'd' | 'e' => {
+                    s = s.strip_prefix(' ').unwrap_or(s);
+                    self.dt.day = number(&mut s, 1, 2)?;
+                }
+                'H' => self.dt.hour = number(&mut s, 1, 2)?,
+                'I' => self.short_hour = Some(number(&mut s, 1, 2)?),
+                'p' | 'P' => {
+                    let meridiem = s.get(..2)?;
+                    self.pm = match meridiem {
+                        _ if meridiem.eq_ignore_ascii_case("am") => false,
+                        _ if meridiem.eq_ignore_ascii_case("pm") => true,
+                        _ => return None,
+                    };
+                    s = &s[2..];
+                }
+                'M' => self.dt.minute = number(&mut s, 1, 2)?,
+                'S' => self.dt.second = number(&mut s, 1, 2)?,
+                'f' => self.dt.nanosecond = fraction(&mut s)?,
+                'z' => {
+                    let len = s.find(|c: char| !"Z+-:0123456789".contains(c));
+                    let (offset, rest) = s.split_at(len.unwrap_or(s.len()));
+                    self.dt.offset = Some(parse_offset(offset)?);
+                    s = rest;
+                }
+                '+' => {
+                    // the date and time may be separated by a space, so take
+                    // the longest prefix that is valid
+                    let ends = s.char_indices().filter(|(_, c)| c.is_whitespace());
+                    let ends = ends.map(|(ix, _)| ix).chain([s.len()]);
+                    let end = ends.rev().find(|&end| parse_iso(&s[..end]).is_some())?;
+                    self.dt = parse_iso(&s[..end])?;
+                    s = &s[end..];
+                }
+                's' => {
+                    let sign = s.starts_with('-') as usize;
+                    let len = sign + s[sign..].bytes().take_while(u8::is_ascii_digit).count();
+                    self.dt = DateTime::from_timestamp(s[..len].parse().ok()?)?;
+                    s = &s[len..];
+                }
+                'F' => s = self.parse("%Y-%m-%d", s)?,
+                'T' => s = self.parse("%H:%M:%S", s)?,
+                '%' => s = s.strip_prefix('%')?,
+                _ => return None,
+            }
+        }
+        Some(s)
+    }
+}
+
+/// Parse a number of between `min` and `max` digits from the start of a value.
+fn number(s: &mut &str, min: usize, max: usize) -> Option<u32> {
+    let len = s.bytes().take(max).take_while(u8::is_ascii_digit).count();
+    if len < min {
+        return None;
+    }
+    let (digits, rest) = s.split_at(len);
+    *s = rest;
+    digits.parse().ok()
+}
+
+/// Parse fractional seconds as nanoseconds, ignoring digits beyond them.
+fn fraction(s: &mut &str) -> Option<u32> {
+    let len = s.bytes().take_while(u8::is_ascii_digit).count();
+    if len == 0 {
+        return None;
+    }
+    let (digits, rest) = s.split_at(len);
+    *s = rest;
+    let digits = &digits[..len.min(9)];
+    Some(digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32))
+}
+
+/// Parse a full or abbreviated month name, ignoring case.
+fn month_name(s: &mut &str) -> Option<u32> {
+    for (ix, name) in MONTHS.iter().enumerate() {
+        for len in [name.len(), 3] {
+            let matches = s
+                .get(..len)
+                .is_some_and(|p| p.eq_ignore_ascii_case(&name[..len]));
+            if matches {
+                *s = &s[len..];
+                return Some(ix as u32 + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Parse a UTC offset such as `Z`, `+01`, `+01:00`, or `-0130` as seconds.
+fn parse_offset(s: &str) -> Option<i32> {
+    if s == "Z" {
+        return Some(0);
+    }
+    let sign = match s.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let s = &s[1..];
+    let (hours, minutes) = match s.split_once(':') {
+        Some(parts) => parts,
+        None if s.len() == 4 => s.split_at(2),
+        None => (s, "00"),
+    };
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours = digits(hours).filter(|&h| h <= 23)?;
+    let minutes = digits(minutes).filter(|&m| m <= 59)?;
+    Some(sign * (hours * 3600 + minutes * 60) as i32)
+}
+
+/// Parse an ISO 8601 date, optionally followed by a time such as `T12:30`,
+/// `T12:30:45.123`, or ` 12:30:45Z`, and a UTC offset.
+fn parse_iso(s: &str) -> Option<DateTime> {
+    let (year, month, day) = parse_iso_date(s.get(..10)?)?;
+    let mut dt = DateTime {
+        year,
+        month,
+        day,
+        ..Default::default()
+    };
+    let time = &s[10..];
+    if time.is_empty() {
+        return Some(dt);
+    }
+    let time = time.strip_prefix(['T', ' '])?;
+    let (time, offset) = match time.find(['Z', '+', '-']) {
+        Some(ix) => time.split_at(ix),
+        None => (time, ""),
+    };
+    if !offset.is_empty() {
+        dt.offset = Some(parse_offset(offset)?);
+    }
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) => (time, Some(fraction)),
+        None => (time, None),
+    };
+    if let Some(mut fraction) = fraction {
+        dt.nanosecond = self::fraction(&mut fraction).filter(|_| fraction.is_empty())?;
+    }
+
+    let limits = [23, 59, 60];
+    let mut parts = [0; 3];
+    let mut count = 0;
+    for part in time.split(':') {
+        let &limit = limits.get(count)?;
+        parts[count] = digits(part).filter(|&value| part.len() == 2 && value <= limit)?;
+        count += 1;
+    }
+    if count < 2 {
+        return None;
+    }
+    [dt.hour, dt.minute, dt.second] = parts;
+    Some(dt)
+}
+
+/// Parse a date in the ISO 8601 format `YYYY-MM-DD`.
+pub(crate) fn parse_iso_date(s: &str) -> Option<(i32, u32, u32)> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let year = digits(&s[0..4])? as i32;
+    let month = digits(&s[5..7])?;
+    let day = digits(&s[8..10])?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+/// Check whether a value is an ISO 8601 date, optionally followed by a time
+/// and a UTC offset.
+pub(crate) fn is_iso_datetime(s: &str) -> bool {
+    parse_iso(s).is_some()
+}
+
+fn digits(s: &str) -> Option<u32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Get the number of days between 1970-01-01 and a date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Get the date that is a number of days after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn datetime(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime {
+        DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(parse_iso_date("2024-02-29"), Some((2024, 2, 29)));
+        assert_eq!(parse_iso_date("2023-02-29"), None);
+        assert_eq!(parse_iso_date("2023-13-01"), None);
+        assert_eq!(parse_iso_date("2023-1-01"), None);
+        assert_eq!(parse_iso_date("20230101"), None);
+    }
+
+    #[test]
+    fn datetimes() {
+        for value in [
+            "2024-01-02",
+            "2024-01-02T03:04",
+            "2024-01-02 03:04:05",
+            "2024-01-02T03:04:05.678Z",
+            "2024-01-02T03:04:05+01:00",
+            "2024-01-02T03:04:05-0130",
+        ] {
+            assert!(is_iso_datetime(value), "{value}");
+        }
+        for value in [
+            "2024-01-02T",
+            "2024-01-02T24:00",
+            "2024-01-02T03",
+            "2024-01-02T03:04:05.",
+            "2024-01-02T03:04+1",
+            "2024-01-02x03:04",
+            "2024-01-02T03:04:05:06",
+        ] {
+            assert!(!is_iso_datetime(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn formats() -> Result<()> {
+        let parse = DateTime::parse_with_format;
+        assert_eq!(
+            parse("18/07/2023 14:05", "%d/%m/%Y %H:%M")?,
+            datetime(2023, 7, 18, 14, 5)
+        );
+        assert_eq!(
+            parse("7/4/23 2:05 pm", "%m/%d/%y %I:%M %p")?,
+            datetime(2023, 7, 4, 14, 5)
+        );
+        assert_eq!(
+            parse("Jul 4, 1999 12:00 AM", "%b %e, %Y %I:%M %p")?,
+            datetime(1999, 7, 4, 0, 0)
+        );
+        assert_eq!(
+            parse("4 September 2023", "%d %B %Y")?,
+            datetime(2023, 9, 4, 0, 0)
+        );
+
+        let dt = parse("2023-07-18 14:05:06.25 +0200", "%F %T.%f %z")?;
+        assert_eq!((dt.second, dt.nanosecond), (6, 250_000_000));
+        assert_eq!(dt.offset, Some(7200));
+        assert_eq!(dt.timestamp(), 1_689_681_906);
+        assert_eq!(dt.to_string(), "2023-07-18T14:05:06.25+02:00");
+
+        assert_eq!(parse("100%", "100%%")?, datetime(1970, 1, 1, 0, 0));
+        assert!(matches!(parse("31/02/2023", "%d/%m/%Y"), Err(Error::Parse)));
+        assert!(matches!(
+            parse("18/07/2023 ", "%d/%m/%Y"),
+            Err(Error::Parse)
+        ));
+        assert!(matches!(parse("13:00 PM", "%I:%M %p"), Err(Error::Parse)));
+        assert!(matches!(parse("2023", "%Q"), Err(Error::Expression(_))));
+        assert!(matches!(parse("2023", "%Y%"), Err(Error::Expression(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn timestamps() -> Result<()> {
+        let dt = DateTime::parse_with_format("1689689100", "%s")?;
+        assert_eq!(dt.to_string(), "2023-07-18T14:05:00Z");
+        assert_eq!(dt.timestamp(), 1_689_689_100);
+        let dt = DateTime::from_timestamp(-1).unwrap();
+        assert_eq!(dt.to_string(), "1969-12-31T23:59:59Z");
+        assert_eq!(DateTime::from_timestamp(951_782_400).unwrap().day, 29);
+        assert_eq!(DateTime::from_timestamp(i64::MAX), None);
+        Ok(())
+    }
+
+    #[test]
+    fn detect() -> Result<()> {
+        let dt: DateTime = "2023-07-18T14:05:00-05:00".parse()?;
+        assert_eq!(dt.offset, Some(-5 * 3600));
+        let dt: DateTime = "2023-07-18 14:05".parse()?;
+        assert_eq!(dt, datetime(2023, 7, 18, 14, 5));
+        let dt: DateTime = "05/07/2023".parse()?;
+        assert_eq!((dt.day, dt.month), (5, 7));
+        let dt: DateTime = "07/18/2023 14:05".parse()?;
+        assert_eq!((dt.day, dt.month), (18, 7));
+        assert!("not a date".parse::<DateTime>().is_err());
+
+        let format = DateTime::detect_format(["18.07.2023", "", "01.08.2023"]);
+        assert_eq!(format, Some("%d.%m.%Y"));
+        assert_eq!(DateTime::detect_format(["18/07/2023", "07/18/2023"]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn default() {
+        let dt = DateTime::default();
+        assert_eq!(dt.to_string(), "1970-01-01T00:00:00");
+        assert_eq!(dt.timestamp(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn chrono() -> Result<()> {
+        let dt: DateTime = "2023-07-18T14:05:06.5+02:00".parse()?;
+        let naive = chrono::NaiveDateTime::try_from(dt)?;
+        assert_eq!(naive.to_string(), "2023-07-18 14:05:06.500");
+        let fixed = chrono::DateTime::<chrono::FixedOffset>::try_from(dt)?;
+        assert_eq!(fixed.timestamp(), dt.timestamp());
+
+        let leap: DateTime = "2016-12-31T23:59:60Z".parse()?;
+        let naive = chrono::NaiveDateTime::try_from(leap)?;
+        assert_eq!(naive.to_string(), "2016-12-31 23:59:60");
+
+        let invalid = DateTime {
+            month: 13,
+            ..Default::default()
+        };
+        assert!(matches!(
+            chrono::NaiveDateTime::try_from(invalid),
+            Err(Error::Unrepresentable)
+        ));
+        let invalid = DateTime {
+            offset: Some(86_400),
+            ..Default::default()
+        };
+        assert!(matches!(
+            chrono::DateTime::<chrono::FixedOffset>::try_from(invalid),
+            Err(Error::Unrepresentable)
+        ));
+        Ok(())
+    }
+}
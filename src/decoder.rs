@@ -0,0 +1,440 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::mem;
+
+use crate::{
+    error::{Error, Result},
+    parser::{project, Quotes},
+    Options,
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum State {
+    /// At the beginning of a record.
+    RecordStart,
+    /// At the beginning of a field following a delimiter.
+    FieldStart,
+    /// Within an unquoted value.
+    Unquoted,
+    /// Within a quoted value.
+    Quoted,
+    /// At a quote within a quoted value, which either closes the value or
+    /// escapes a following quote.
+    QuotedQuote,
+}
+
+/// The outcome of decoding a chunk of input.
+///
+/// Records are `Vec<String>` by default, or `Vec<Option<String>>` when decoded
+/// with `Decoder::decode_opt`.
+#[derive(PartialEq, Debug)]
+pub enum Decoded<T = Vec<String>> {
+    /// A record was completed.
+    Record(T),
+    /// The input was exhausted before a record was completed.
+    NeedMore,
+}
+
+/// A record along with whether each of its fields was quoted.
+type Fields = (Vec<String>, Vec<bool>);
+
+/// A method of `Decoder` that decodes a chunk of input into records of type
+/// `T`, shared by the streaming readers.
+#[cfg(feature = "std")]
+pub(crate) type DecodeFn<T> = fn(&mut Decoder, &[u8]) -> Result<(usize, Decoded<T>)>;
+
+/// A method of `Decoder` that signals the end of the input.
+#[cfg(feature = "std")]
+pub(crate) type FinishFn<T> = fn(&mut Decoder) -> Result<Option<T>>;
+
+/// An incremental push parser over delimiter-separated values.
+///
+/// A `Decoder` is fed chunks of bytes of any size and yields records as they
+/// are completed. Chunks may end anywhere, including within a quoted value,
+/// between the quotes of an escaped quote, between `\r` and `\n`, or within a
+/// multibyte character. The decoder performs no I/O of its own.
+///
+/// The decoder accepts the same syntax as the in-memory parser and produces
+/// the same records. It is shared by the streaming readers so that they behave
+/// identically.
+pub struct Decoder {
+    delimiter: u8,
+    quotes: Quotes,
+    projection: Option<Vec<usize>>,
+    null_values: Vec<String>,
+    state: State,
+    /// Whether the last byte was a `\r` that may start a `\r\n` newline.
+    pending_cr: bool,
+    field: Vec<u8>,
+    record: Vec<String>,
+    /// Whether each field of the current record was quoted.
+    quoted: Vec<bool>,
+    field_quoted: bool,
+    num_expected_fields: Option<usize>,
+    /// The number of lines still to be skipped.
+    skip_rows: usize,
+}
+
+impl Decoder {
+    /// Create a decoder.
+    pub fn new() -> Decoder {
+        Decoder::with_options(Options::default())
+    }
+
+    /// Create a decoder with options.
+    pub fn with_options(options: Options) -> Decoder {
+        Decoder {
+            delimiter: options.delimiter,
+            quotes: options.quotes,
+            projection: options.projection,
+            null_values: options.null_values,
+            state: State::RecordStart,
+            pending_cr: false,
+            field: vec![],
+            record: vec![],
+            quoted: vec![],
+            field_quoted: false,
+            num_expected_fields: None,
+            skip_rows: options.skip_rows,
+        }
+    }
+
+    /// Decode input until a record is completed or the input is exhausted.
+    ///
+    /// Returns the number of bytes consumed along with the outcome. Input
+    /// following a completed record is not consumed and should be passed to
+    /// the next call. If more input is needed, all of the input was consumed.
+    pub fn decode(&mut self, input: &[u8]) -> Result<(usize, Decoded)> {
+        let (consumed, record) = self.decode_fields(input)?;
+        let decoded = match record {
+            Some((record, _)) => Decoded::Record(record),
+            None => Decoded::NeedMore,
+        };
+        Ok((consumed, decoded))
+    }
+
+    /// Decode input like `decode`, with `None` in place of fields that match
+    /// one of `Options::null_values`.
+    pub fn decode_opt(&mut self, input: &[u8]) -> Result<(usize, Decoded<Vec<Option<String>>>)> {
+        let (consumed, record) = self.decode_fields(input)?;
+        let decoded = match record {
+            Some((record, quoted)) => Decoded::Record(self.nullify(record, quoted)),
+            None => Decoded::NeedMore,
+        };
+        Ok((consumed, decoded))
+    }
+
+    /// Signal the end of the input.
+    ///
+    /// Returns the final record if the input did not end with a newline.
+    pub fn finish(&mut self) -> Result<Option<Vec<String>>> {
+        Ok(self.finish_fields()?.map(|(record, _)| record))
+    }
+
+    /// Signal the end of the input like `finish`, with `None` in place of
+    /// fields that match one of `Options::null_values`.
+    pub fn finish_opt(&mut self) -> Result<Option<Vec<Option<String>>>> {
+        let record = self.finish_fields()?;
+        Ok(record.map(|(record, quoted)| self.nullify(record, quoted)))
+    }
+
+    /// Decode input until a record is completed, returning its fields and
+    /// whether each one was quoted.
+    fn decode_fields(&mut self, input: &[u8]) -> Result<(usize, Option<Fields>)> {
+        for (ix, &byte) in input.iter().enumerate() {
+            if self.skip_rows > 0 {
+                self.skip_rows -= (byte == b'\n') as usize;
+                continue;
+            }
+            if self.step(byte)? {
+                return Ok((ix + 1, Some(self.take_record()?)));
+            }
+        }
+        Ok((input.len(), None))
+    }
+
+    fn finish_fields(&mut self) -> Result<Option<Fields>> {
+        if self.pending_cr {
+            self.pending_cr = false;
+            self.step_byte(b'\r')?;
+        }
+        match self.state {
+            State::RecordStart => return Ok(None),
+            // disallow unterminated quotes
+            State::Quoted => return Err(Error::Parse),
+            State::FieldStart | State::Unquoted | State::QuotedQuote => self.end_field()?,
+        }
+        Ok(Some(self.take_record()?))
+    }
+
+    /// Replace the unquoted fields that match a null value with `None`.
+    fn nullify(&self, record: Vec<String>, quoted: Vec<bool>) -> Vec<Option<String>> {
+        record
+            .into_iter()
+            .zip(quoted)
+            .map(|(value, quoted)| {
+                let null = !quoted && self.null_values.contains(&value);
+                (!null).then_some(value)
+            })
+            .collect()
+    }
+
+    /// Consume one byte, returning whether it completed a record.
+    fn step(&mut self, byte: u8) -> Result<bool> {
+        if self.pending_cr {
+            self.pending_cr = false;
+            if byte == b'\n' {
+                return self.step_byte(byte);
+            }
+            // a lone `\r` is not a newline
+            self.step_byte(b'\r')?;
+        }
+        if byte == b'\r' && self.state != State::Quoted {
+            self.pending_cr = true;
+            return Ok(false);
+        }
+        self.step_byte(byte)
+    }
+
+    /// Consume one byte, treating `\r` like any other character.
+    fn step_byte(&mut self, byte: u8) -> Result<bool> {
+        use State::*;
+
+        match self.state {
+            RecordStart | FieldStart => {
+                if byte == self.delimiter {
+                    self.end_field()?;
+                    self.state = FieldStart;
+                } else if byte == b'\n' {
+                    // an empty line is a record without fields
+                    if self.state == FieldStart {
+                        self.end_field()?;
+                    }
+                    return Ok(true);
+                } else if byte == b'"' && self.quotes == Quotes::Significant {
+                    self.state = Quoted;
+                    self.field_quoted = true;
+                } else {
+                    self.push(byte);
+                    self.state = Unquoted;
+                }
+            }
+            Unquoted => {
+                if byte == self.delimiter {
+                    self.end_field()?;
+                    self.state = FieldStart;
+                } else if byte == b'\n' {
+                    self.end_field()?;
+                    return Ok(true);
+                } else {
+                    self.push(byte);
+                }
+            }
+            Quoted => {
+                if byte == b'"' {
+                    self.state = QuotedQuote;
+                } else {
+                    self.push(byte);
+                }
+            }
+            QuotedQuote => {
+                if byte == b'"' {
+                    self.push(byte);
+                    self.state = Quoted;
+                } else if byte == self.delimiter {
+                    self.end_field()?;
+                    self.state = FieldStart;
+                } else if byte == b'\n' {
+                    self.end_field()?;
+                    return Ok(true);
+                } else {
+                    // disallow consecutive values
+                    return Err(Error::Parse);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Check whether the current field is part of the projection.
+    fn wanted(&self) -> bool {
+        match &self.projection {
+            Some(projection) => projection.contains(&self.record.len()),
+            None => true,
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        // unselected fields are skipped without being copied
+        if self.wanted() {
+            self.field.push(byte);
+        }
+    }
+
+    fn end_field(&mut self) -> Result<()> {
+        let field = mem::take(&mut self.field);
+        let field = String::from_utf8(field).map_err(|_| Error::Encoding)?;
+        self.record.push(field);
+        self.quoted.push(mem::take(&mut self.field_quoted));
+        Ok(())
+    }
+
+    fn take_record(&mut self) -> Result<Fields> {
+        self.state = State::RecordStart;
+        let record = mem::take(&mut self.record);
+        let quoted = mem::take(&mut self.quoted);
+        match self.num_expected_fields {
+            None => self.num_expected_fields = Some(record.len()),
+            Some(len) if record.len() != len => return Err(Error::Parse),
+            _ => {}
+        }
+        match &self.projection {
+            Some(projection) => Ok((project(record, projection)?, project(quoted, projection)?)),
+            None => Ok((record, quoted)),
+        }
+    }
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Decoder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dsv;
+
+    /// Decode the input in chunks of the specified size.
+    fn decode_chunked(text: &str, size: usize) -> Result<Vec<Vec<String>>> {
+        let mut decoder = Decoder::new();
+        let mut records = vec![];
+        for mut chunk in text.as_bytes().chunks(size) {
+            while let (consumed, Decoded::Record(record)) = decoder.decode(chunk)? {
+                records.push(record);
+                chunk = &chunk[consumed..];
+            }
+        }
+        records.extend(decoder.finish()?);
+        Ok(records)
+    }
+
+    #[test]
+    fn matches_parser() -> Result<()> {
+        let inputs = [
+            "foo",
+            "foo,bar\nbaz,bux\n",
+            "foo,bar\r\nbaz,bux\r\n",
+            "foo\rbar,baz\r",
+            ",foo",
+            "foo,",
+            ",,",
+            "foo,,bar",
+            "\"foo\r\nbar\",\"ba\"\"z\"",
+            "\"\",x\r\n\"\"\"\",y",
+            "a\"b,c",
+            "Los Angeles,34°03′N,118°15′W",
+            "1997,Ford,E350,\"Super, \"\"luxurious\"\" truck\"",
+        ];
+        for input in inputs {
+            let expected = Dsv::from_str(input)?;
+            for size in 1..=input.len() {
+                let records = decode_chunked(input, size)?;
+                assert_eq!(records, expected.records(), "{input:?} in chunks of {size}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn skip_rows() -> Result<()> {
+        let text = "title\r\n\"a\nb\",c\nfoo,bar\n";
+        for size in 1..=text.len() {
+            let options = Options {
+                skip_rows: 2,
+                ..Default::default()
+            };
+            let mut decoder = Decoder::with_options(options);
+            let mut records = vec![];
+            for mut chunk in text.as_bytes().chunks(size) {
+                while let (consumed, Decoded::Record(record)) = decoder.decode(chunk)? {
+                    records.push(record);
+                    chunk = &chunk[consumed..];
+                }
+            }
+            records.extend(decoder.finish()?);
+            assert_eq!(records, [["b\"", "c"], ["foo", "bar"]], "chunks of {size}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn projection() -> Result<()> {
+        let options = Options {
+            projection: Some(vec![1]),
+            ..Default::default()
+        };
+        let mut decoder = Decoder::with_options(options);
+        let (_, decoded) = decoder.decode(b"foo,bar\n")?;
+        assert_eq!(decoded, Decoded::Record(vec!["bar".into()]));
+        Ok(())
+    }
+
+    #[test]
+    fn null_values() -> Result<()> {
+        let options = Options {
+            null_values: vec!["".into(), "NA".into()],
+            projection: Some(vec![2, 0, 1]),
+            ..Default::default()
+        };
+        let text = "NA,,\"\"\n\"NA\",x,\n";
+        let mut expected = vec![];
+        let mut reader = crate::Reader::from_str_with_options(text, options.clone());
+        while let Some(record) = reader.next_record_opt() {
+            let record: Vec<_> = record?.into_iter().map(|f| f.map(String::from)).collect();
+            expected.push(record);
+        }
+        assert_eq!(expected[0], [Some("".into()), None, None]);
+
+        for size in 1..=text.len() {
+            let mut decoder = Decoder::with_options(options.clone());
+            let mut records = vec![];
+            for mut chunk in text.as_bytes().chunks(size) {
+                while let (consumed, Decoded::Record(record)) = decoder.decode_opt(chunk)? {
+                    records.push(record);
+                    chunk = &chunk[consumed..];
+                }
+            }
+            records.extend(decoder.finish_opt()?);
+            assert_eq!(records, expected, "chunks of {size}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn need_more() -> Result<()> {
+        let mut decoder = Decoder::new();
+        assert_eq!(decoder.decode(b"\"foo\"")?, (5, Decoded::NeedMore));
+        assert_eq!(decoder.decode(b"\"bar\"\r")?, (6, Decoded::NeedMore));
+        let (consumed, decoded) = decoder.decode(b"\nbaz")?;
+        assert_eq!(consumed, 1);
+        assert_eq!(decoded, Decoded::Record(vec!["foo\"bar".into()]));
+        Ok(())
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        assert!(decode_chunked("\"foo", 1).is_err());
+    }
+
+    #[test]
+    fn consecutive_values() {
+        assert!(decode_chunked("\"foo\"bar", 1).is_err());
+        assert!(decode_chunked("\"foo\"\rbar", 1).is_err());
+    }
+
+    #[test]
+    fn mismatched_field_count() {
+        assert!(decode_chunked("foo,bar\nbaz", 1).is_err());
+    }
+}
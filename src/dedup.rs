@@ -0,0 +1,280 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::cmp::Reverse;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+use crate::{column::Column, error::Result, Dsv};
+
+/// Which occurrence of a duplicate record to keep.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Keep {
+    #[default]
+    First,
+    Last,
+}
+
+impl Dsv {
+    /// Remove records that are identical to another record.
+    ///
+    /// A null field is not identical to an empty one.
+    pub fn dedup(&mut self, keep: Keep) {
+        let columns: Vec<usize> = (0..self.width()).collect();
+        self.dedup_columns(&columns, keep);
+    }
+
+    /// Remove records whose fields in the specified columns are identical to
+    /// those of another record.
+    pub fn dedup_by<'c, C: Copy + Into<Column<'c>>>(
+        &mut self,
+        columns: &[C],
+        keep: Keep,
+    ) -> Result<()> {
+        let columns = self.resolve_columns(columns)?;
+        self.dedup_columns(&columns, keep);
+        Ok(())
+    }
+
+    fn dedup_columns(&mut self, columns: &[usize], keep: Keep) {
+        let mut seen = BTreeSet::new();
+        let len = self.records.len();
+        let mut retain: Vec<bool> = match keep {
+            Keep::First => (0..len)
+                .map(|ix| seen.insert(key(self, ix, columns)))
+                .collect(),
+            Keep::Last => (0..len)
+                .rev()
+                .map(|ix| seen.insert(key(self, ix, columns)))
+                .collect(),
+        };
+        if keep == Keep::Last {
+            retain.reverse();
+        }
+
+        self.retain_indexes(&retain);
+    }
+
+    /// Get the distinct values in a column, in order of first appearance, with
+    /// `None` for null fields.
+    pub fn distinct<'c, C: Into<Column<'c>>>(&self, column: C) -> Result<Vec<Option<&str>>> {
+        let column = column.into().resolve(self.headers(), self.width())?;
+        let mut seen = BTreeSet::new();
+        Ok((0..self.records.len())
+            .map(|ix| self.field_opt(ix, column))
+            .filter(|value| seen.insert(*value))
+            .collect())
+    }
+
+    /// Count the occurrences of each value in a column, with `None` for null
+    /// fields.
+    ///
+    /// Values are ordered from most to least frequent, and otherwise in order
+    /// of first appearance.
+    pub fn value_counts<'c, C: Into<Column<'c>>>(
+        &self,
+        column: C,
+    ) -> Result<Vec<(Option<&str>, usize)>> {
+        let column = column.into().resolve(self.headers(), self.width())?;
+        let mut lookup = BTreeMap::new();
+        let mut counts: Vec<(Option<&str>, usize)> = Vec::new();
+        for ix in 0..self.records.len() {
+            let value = self.field_opt(ix, column);
+            let ix = *lookup.entry(value).or_insert_with(|| {
+                counts.push((value, 0));
+                counts.len() - 1
+            });
+            counts[ix].1 += 1;
+        }
+        counts.sort_by_key(|&(_, count)| Reverse(count));
+        Ok(counts)
+    }
+}
+
+/// Get the key of a record, with `None` for null fields.
+fn key<'a>(dsv: &'a Dsv, record: usize, columns: &[usize]) -> Vec<Option<&'a str>> {
+    columns.iter().map(|&c| dsv.field_opt(record, c)).collect()
+}
+
+/// An iterator adapter that skips duplicate records in a stream.
+///
+/// The first occurrence of each record is kept. Only the keys of the records
+/// seen so far are held in memory, in a hash set. Errors are passed through.
+///
+/// ```
+/// use dsv::{Dedup, StreamReader};
+///
+/// let input = "apples,2\noranges,3\napples,4\n";
+/// let records = StreamReader::new(input.as_bytes());
+/// let fruits: Vec<_> = Dedup::by(records, vec![0]).map(|r| r.unwrap()).collect();
+///
+/// assert_eq!(fruits, [["apples", "2"], ["oranges", "3"]]);
+/// ```
+#[cfg(feature = "std")]
+pub struct Dedup<I> {
+    records: I,
+    columns: Option<Vec<usize>>,
+    seen: HashSet<Vec<String>>,
+}
+
+#[cfg(feature = "std")]
+impl<I> Dedup<I> {
+    /// Skip records that are identical to an earlier record.
+    pub fn new(records: I) -> Dedup<I> {
+        Dedup {
+            records,
+            columns: None,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Skip records whose fields in the specified columns are identical to
+    /// those of an earlier record.
+    ///
+    /// A record that does not have one of the columns is returned as
+    /// `Error::OutOfBounds`.
+    pub fn by(records: I, columns: Vec<usize>) -> Dedup<I> {
+        Dedup {
+            records,
+            columns: Some(columns),
+            seen: HashSet::new(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, T> Iterator for Dedup<I>
+where
+    I: Iterator<Item = Result<Vec<T>>>,
+    T: AsRef<str>,
+{
+    type Item = Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.records.next()? {
+                Ok(record) => record,
+                Err(error) => return Some(Err(error)),
+            };
+            let key = match &self.columns {
+                Some(columns) => match columns
+                    .iter()
+                    .map(|&c| record.get(c).map(|f| f.as_ref().into()))
+                    .collect()
+                {
+                    Some(key) => key,
+                    None => return Some(Err(crate::Error::OutOfBounds)),
+                },
+                None => record.iter().map(|f| f.as_ref().into()).collect(),
+            };
+            if self.seen.insert(key) {
+                return Some(Ok(record));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsvBuilder, Options};
+
+    fn fruits() -> Dsv {
+        DsvBuilder::new()
+            .headers(["fruit", "count"])
+            .record(["apples", "2"])
+            .record(["oranges", "3"])
+            .record(["apples", "2"])
+            .record(["apples", "4"])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn dedup() {
+        let mut dsv = fruits();
+        dsv.dedup(Keep::First);
+        assert_eq!(
+            dsv.records(),
+            [["apples", "2"], ["oranges", "3"], ["apples", "4"]]
+        );
+    }
+
+    #[test]
+    fn dedup_by() -> Result<()> {
+        let mut first = fruits();
+        first.dedup_by(&["fruit"], Keep::First)?;
+        assert_eq!(first.records(), [["apples", "2"], ["oranges", "3"]]);
+
+        let mut last = fruits();
+        last.dedup_by(&[0], Keep::Last)?;
+        assert_eq!(last.records(), [["oranges", "3"], ["apples", "4"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn distinct() -> Result<()> {
+        let dsv = fruits();
+        assert_eq!(dsv.distinct("fruit")?, [Some("apples"), Some("oranges")]);
+        assert_eq!(dsv.distinct(1)?, [Some("2"), Some("3"), Some("4")]);
+        Ok(())
+    }
+
+    #[test]
+    fn value_counts() -> Result<()> {
+        let dsv = fruits();
+        assert_eq!(
+            dsv.value_counts("count")?,
+            [(Some("2"), 2), (Some("3"), 1), (Some("4"), 1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let options = Options {
+            null_values: vec!["NA".into()],
+            ..Default::default()
+        };
+        let mut dsv = Dsv::from_str_with_options("NA,1\n,2\nNA,3\n,4", options)?;
+        assert_eq!(dsv.distinct(0)?, [None, Some("")]);
+        assert_eq!(dsv.value_counts(0)?, [(None, 2), (Some(""), 2)]);
+        dsv.dedup_by(&[0], Keep::First)?;
+        assert_eq!(dsv.records(), [["", "1"], ["", "2"]]);
+        assert!(dsv.is_null(0, 0) && !dsv.is_null(1, 0));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn streaming() -> Result<()> {
+        let records = vec![
+            Ok(vec!["a", "1"]),
+            Ok(vec!["a", "1"]),
+            Err(crate::Error::Parse),
+            Ok(vec!["a", "2"]),
+        ];
+        let mut dedup = Dedup::new(records.into_iter());
+        assert_eq!(dedup.next().unwrap()?, ["a", "1"]);
+        assert!(dedup.next().unwrap().is_err());
+        assert_eq!(dedup.next().unwrap()?, ["a", "2"]);
+        assert!(dedup.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn streaming_by_missing_column() -> Result<()> {
+        let records = vec![Ok(vec!["a", "1"]), Ok(vec!["b"]), Ok(vec!["a", "2"])];
+        let mut dedup = Dedup::by(records.into_iter(), vec![1]);
+        assert_eq!(dedup.next().unwrap()?, ["a", "1"]);
+        assert!(matches!(
+            dedup.next().unwrap(),
+            Err(crate::Error::OutOfBounds)
+        ));
+        assert_eq!(dedup.next().unwrap()?, ["a", "2"]);
+        assert!(dedup.next().is_none());
+        Ok(())
+    }
+}
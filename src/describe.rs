@@ -0,0 +1,685 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use core::{cmp::Reverse, iter};
+
+use crate::{convert::NumberFormat, Dsv};
+
+/// The number of distinct values that are counted exactly before the count is
+/// estimated.
+const EXACT_DISTINCT: usize = 4096;
+
+/// The number of registers in the distinct count estimate, as a power of two.
+const PRECISION: u32 = 12;
+
+/// Statistics of one column.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ColumnSummary {
+    /// The header of the column, or its index if there are no headers.
+    pub name: String,
+    /// The number of values that are not null.
+    pub count: u64,
+    /// The number of null values.
+    pub nulls: u64,
+    /// The number of distinct values that are not null. This is exact for up
+    /// to 4096 distinct values and estimated beyond that.
+    pub distinct: u64,
+    /// The smallest value, compared as numbers if the column is numeric.
+    pub min: Option<String>,
+    /// The largest value, compared as numbers if the column is numeric.
+    pub max: Option<String>,
+    /// The length of the shortest value in characters.
+    pub min_length: Option<usize>,
+    /// The length of the longest value in characters.
+    pub max_length: Option<usize>,
+    /// The most frequent values and their counts, from most to least
+    /// frequent. The counts are exact unless the column has more distinct
+    /// values than are tracked, in which case they may be overestimated.
+    pub top: Vec<(String, u64)>,
+    /// Statistics of the values as numbers, if every value that is not null is
+    /// a number.
+    pub numeric: Option<NumericSummary>,
+}
+
+/// Statistics of a numeric column.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NumericSummary {
+    /// The smallest value.
+    pub min: f64,
+    /// The largest value.
+    pub max: f64,
+    /// The arithmetic mean of the values.
+    pub mean: f64,
+    /// The sample standard deviation, which is zero for a single value.
+    pub stddev: f64,
+    /// Each requested quantile and its value. Quantiles are exact for up to
+    /// five values and estimated beyond that.
+    pub quantiles: Vec<(f64, f64)>,
+}
+
+/// Options for column statistics.
+#[derive(Clone, Debug)]
+pub struct DescribeOptions {
+    /// Values that are treated as null. Default: the empty string.
+    pub null_values: Vec<String>,
+    /// The format of numbers. Default: `NumberFormat::default()`.
+    pub number_format: NumberFormat,
+    /// The quantiles to estimate for numeric columns, between 0 and 1.
+    /// Default: 0.25, 0.5, and 0.75.
+    pub quantiles: Vec<f64>,
+    /// The number of most frequent values to report. Default: 5.
+    pub top: usize,
+}
+
+impl Default for DescribeOptions {
+    fn default() -> Self {
+        Self {
+            null_values: vec!["".into()],
+            number_format: NumberFormat::default(),
+            quantiles: vec![0.25, 0.5, 0.75],
+            top: 5,
+        }
+    }
+}
+
+/// Computes column statistics from records one at a time.
+///
+/// Memory use is bounded regardless of the number of records, so a
+/// `Describer` can summarize inputs that do not fit in memory. Records may
+/// have different numbers of fields, and a column that a record does not
+/// reach is counted as null for that record.
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use dsv::{DescribeOptions, Describer, StreamReader};
+///
+/// let mut describer = Describer::new(DescribeOptions::default());
+/// let mut records = StreamReader::new("fruit,count\napples,2\npears,\nkiwis,7\n".as_bytes());
+/// describer.headers(&records.next().unwrap().unwrap());
+/// for record in records {
+///     describer.add_record(&record.unwrap());
+/// }
+///
+/// let summary = describer.finish();
+/// assert_eq!(summary[1].name, "count");
+/// assert_eq!(summary[1].nulls, 1);
+/// assert_eq!(summary[1].numeric.as_ref().unwrap().mean, 4.5);
+/// # }
+/// ```
+pub struct Describer {
+    options: DescribeOptions,
+    names: Vec<String>,
+    columns: Vec<ColumnState>,
+    /// The number of records added so far.
+    records: u64,
+}
+
+impl Describer {
+    /// Create a describer with options.
+    pub fn new(options: DescribeOptions) -> Describer {
+        Describer {
+            options,
+            names: vec![],
+            columns: vec![],
+            records: 0,
+        }
+    }
+
+    /// Name the columns by their headers.
+    pub fn headers<T: AsRef<str>>(&mut self, headers: &[T]) {
+        self.names = headers.iter().map(|h| h.as_ref().into()).collect();
+    }
+
+    /// Add the next record.
+    pub fn add_record<T: AsRef<str>>(&mut self, record: &[T]) {
+        self.add(record.iter().map(|value| Some(value.as_ref())));
+    }
+
+    /// Add the next record, where a field of `None` is null. This accepts the
+    /// records of `Reader::next_record_opt`.
+    pub fn add_record_opt<T: AsRef<str>>(&mut self, record: &[Option<T>]) {
+        self.add(record.iter().map(|value| value.as_ref().map(AsRef::as_ref)));
+    }
+
+    fn add<'v>(&mut self, record: impl ExactSizeIterator<Item = Option<&'v str>>) {
+        if self.columns.len() < record.len() {
+            let (quantiles, records) = (&self.options.quantiles, self.records);
+            self.columns.resize_with(record.len(), || {
+                // the column was missing from every earlier record
+                let mut column = ColumnState::new(quantiles);
+                column.nulls = records;
+                column
+            });
+        }
+        let values = record.chain(iter::repeat(None));
+        for (column, value) in self.columns.iter_mut().zip(values) {
+            match value.filter(|v| !self.options.null_values.iter().any(|null| null == v)) {
+                Some(value) => column.add(value, &self.options),
+                None => column.nulls += 1,
+            }
+        }
+        self.records += 1;
+    }
+
+    /// Get the statistics of each column.
+    pub fn finish(self) -> Vec<ColumnSummary> {
+        let options = &self.options;
+        self.columns
+            .into_iter()
+            .enumerate()
+            .map(|(ix, column)| {
+                let name = match self.names.get(ix) {
+                    Some(name) => name.clone(),
+                    None => ix.to_string(),
+                };
+                column.finish(name, options)
+            })
+            .collect()
+    }
+}
+
+impl Dsv {
+    /// Compute statistics of each column.
+    pub fn describe(&self) -> Vec<ColumnSummary> {
+        self.describe_with_options(DescribeOptions::default())
+    }
+
+    /// Compute statistics of each column with options.
+    ///
+    /// Null fields are counted as nulls in addition to the null values of the
+    /// options.
+    pub fn describe_with_options(&self, options: DescribeOptions) -> Vec<ColumnSummary> {
+        let mut describer = Describer::new(options);
+        if let Some(headers) = self.headers() {
+            describer.headers(headers);
+        }
+        for (index, record) in self.records.iter().enumerate() {
+            describer.add(
+                record.iter().enumerate().map(|(column, value)| {
+                    (!self.is_null(index, column)).then_some(value.as_str())
+                }),
+            );
+        }
+        describer.finish()
+    }
+}
+
+/// The running statistics of one column.
+struct ColumnState {
+    count: u64,
+    nulls: u64,
+    distinct: Distinct,
+    min: Option<String>,
+    max: Option<String>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    top: SpaceSaving,
+    /// The numeric statistics, until a value that is not a number is added.
+    numeric: Option<NumericState>,
+}
+
+struct NumericState {
+    min: (f64, String),
+    max: (f64, String),
+    mean: f64,
+    /// The sum of squared differences from the mean.
+    m2: f64,
+    quantiles: Vec<Quantile>,
+}
+
+impl ColumnState {
+    fn new(quantiles: &[f64]) -> ColumnState {
+        ColumnState {
+            count: 0,
+            nulls: 0,
+            distinct: Distinct::Exact(BTreeSet::new()),
+            min: None,
+            max: None,
+            min_length: None,
+            max_length: None,
+            top: SpaceSaving::default(),
+            numeric: Some(NumericState {
+                min: (f64::INFINITY, String::new()),
+                max: (f64::NEG_INFINITY, String::new()),
+                mean: 0.0,
+                m2: 0.0,
+                quantiles: quantiles.iter().map(|&p| Quantile::new(p)).collect(),
+            }),
+        }
+    }
+
+    fn add(&mut self, value: &str, options: &DescribeOptions) {
+        self.count += 1;
+        self.distinct.add(value);
+        if self.min.as_deref().is_none_or(|min| value < min) {
+            self.min = Some(value.into());
+        }
+        if self.max.as_deref().is_none_or(|max| value > max) {
+            self.max = Some(value.into());
+        }
+        let length = value.chars().count();
+        self.min_length = Some(self.min_length.map_or(length, |min| min.min(length)));
+        self.max_length = Some(self.max_length.map_or(length, |max| max.max(length)));
+        self.top.add(value, options.top);
+
+        let Some(numeric) = &mut self.numeric else {
+            return;
+        };
+        let Ok(number) = options.number_format.parse_f64(value) else {
+            self.numeric = None;
+            return;
+        };
+        if number < numeric.min.0 {
+            numeric.min = (number, value.into());
+        }
+        if number > numeric.max.0 {
+            numeric.max = (number, value.into());
+        }
+        let delta = number - numeric.mean;
+        numeric.mean += delta / self.count as f64;
+        numeric.m2 += delta * (number - numeric.mean);
+        for quantile in &mut numeric.quantiles {
+            quantile.add(number);
+        }
+    }
+
+    fn finish(self, name: String, options: &DescribeOptions) -> ColumnSummary {
+        let numeric = self.numeric.filter(|_| self.count > 0);
+        let (min, max) = match &numeric {
+            Some(numeric) => (Some(numeric.min.1.clone()), Some(numeric.max.1.clone())),
+            None => (self.min, self.max),
+        };
+        ColumnSummary {
+            name,
+            count: self.count,
+            nulls: self.nulls,
+            distinct: self.distinct.count(),
+            min,
+            max,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            top: self.top.top(options.top),
+            numeric: numeric.map(|numeric| NumericSummary {
+                min: numeric.min.0,
+                max: numeric.max.0,
+                mean: numeric.mean,
+                stddev: match self.count {
+                    1 => 0.0,
+                    count => sqrt(numeric.m2 / (count - 1) as f64),
+                },
+                quantiles: numeric
+                    .quantiles
+                    .iter()
+                    .map(|quantile| (quantile.p, quantile.value(numeric.min.0, numeric.max.0)))
+                    .collect(),
+            }),
+        }
+    }
+}
+
+/// A count of distinct values, which is exact until there are too many values
+/// to track and then estimated with HyperLogLog.
+enum Distinct {
+    Exact(BTreeSet<u64>),
+    Estimated(Vec<u8>),
+}
+
+impl Distinct {
+    fn add(&mut self, value: &str) {
+        let hash = hash(value);
+        match self {
+            Distinct::Exact(hashes) => {
+                hashes.insert(hash);
+                if hashes.len() > EXACT_DISTINCT {
+                    let mut registers = vec![0; 1 << PRECISION];
+                    for &hash in hashes.iter() {
+                        register(&mut registers, hash);
+                    }
+                    *self = Distinct::Estimated(registers);
+                }
+            }
+            Distinct::Estimated(registers) => register(registers, hash),
+        }
+    }
+
+    fn count(&self) -> u64 {
+        let registers = match self {
+            Distinct::Exact(hashes) => return hashes.len() as u64,
+            Distinct::Estimated(registers) => registers,
+        };
+        let m = registers.len() as f64;
+        let sum: f64 = registers.iter().map(|&r| 1.0 / (1u64 << r) as f64).sum();
+        let estimate = 0.7213 / (1.0 + 1.079 / m) * m * m / sum;
+        let zeros = registers.iter().filter(|&&r| r == 0).count();
+        // linear counting is more accurate for small counts
+        let estimate = if estimate <= 2.5 * m && zeros > 0 {
+            m * ln(m / zeros as f64)
+        } else {
+            estimate
+        };
+        (estimate + 0.5) as u64
+    }
+}
+
+/// Update the HyperLogLog register of a hash.
+fn register(registers: &mut [u8], hash: u64) {
+    let index = (hash >> (64 - PRECISION)) as usize;
+    let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+    registers[index] = registers[index].max(rank);
+}
+
+/// Hash a value with FNV-1a, mixed so that every bit depends on the input.
+fn hash(value: &str) -> u64 {
+    let mut hash = value.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// The most frequent values, counted with the Space-Saving algorithm.
+#[derive(Default)]
+struct SpaceSaving {
+    counts: BTreeMap<String, u64>,
+}
+
+impl SpaceSaving {
+    fn add(&mut self, value: &str, top: usize) {
+        if let Some(count) = self.counts.get_mut(value) {
+            *count += 1;
+            return;
+        }
+        if top == 0 {
+            return;
+        }
+        // track more values than are reported so that the top counts are
+        // accurate for columns with many distinct values
+        if self.counts.len() < (top * 20).max(100) {
+            self.counts.insert(value.into(), 1);
+            return;
+        }
+        // replace the least frequent value, inheriting its count
+        let (least, &count) = self.counts.iter().min_by_key(|&(_, count)| count).unwrap();
+        let least = least.clone();
+        self.counts.remove(&least);
+        self.counts.insert(value.into(), count + 1);
+    }
+
+    fn top(self, top: usize) -> Vec<(String, u64)> {
+        let mut counts: Vec<_> = self.counts.into_iter().collect();
+        // stable, so ties are in order of value
+        counts.sort_by_key(|&(_, count)| Reverse(count));
+        counts.truncate(top);
+        counts
+    }
+}
+
+/// A quantile estimated with the P² algorithm, which keeps five markers whose
+/// heights approximate the minimum, the quantile, the maximum, and the
+/// quantiles halfway between them.
+struct Quantile {
+    p: f64,
+    heights: Vec<f64>,
+    positions: [f64; 5],
+    desired: [f64; 5],
+}
+
+impl Quantile {
+    fn new(p: f64) -> Quantile {
+        Quantile {
+            p,
+            heights: Vec::with_capacity(5),
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+        }
+    }
+
+    fn add(&mut self, x: f64) {
+        let q = &mut self.heights;
+        if q.len() < 5 {
+            let ix = q.partition_point(|&h| h <= x);
+            q.insert(ix, x);
+            return;
+        }
+
+        let k = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| x < q[i + 1]).unwrap()
+        };
+        let n = &mut self.positions;
+        for position in &mut n[k + 1..] {
+            *position += 1.0;
+        }
+        let p = self.p;
+        for (desired, increment) in
+            self.desired
+                .iter_mut()
+                .zip([0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0])
+        {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let d = self.desired[i] - n[i];
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = if d > 0.0 { 1.0 } else { -1.0 };
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                n[i] += d;
+            }
+        }
+    }
+
+    /// Get the estimate, given the minimum and maximum of the values.
+    fn value(&self, min: f64, max: f64) -> f64 {
+        let q = &self.heights;
+        if self.p <= 0.0 {
+            return min;
+        }
+        if self.p >= 1.0 {
+            return max;
+        }
+        if q.len() < 5 {
+            // interpolate between the closest values
+            let rank = self.p * (q.len() - 1) as f64;
+            let below = rank as usize;
+            let above = (below + 1).min(q.len() - 1);
+            return q[below] + (rank - below as f64) * (q[above] - q[below]);
+        }
+        q[2]
+    }
+}
+
+/// The square root.
+#[cfg(feature = "std")]
+fn sqrt(x: f64) -> f64 {
+    x.sqrt()
+}
+
+/// The square root, which `core` does not provide.
+#[cfg(not(feature = "std"))]
+fn sqrt(x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    // Newton's method converges from any guess above the root
+    let mut root = if x > 1.0 { x } else { 1.0 };
+    loop {
+        let next = (root + x / root) / 2.0;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// The natural logarithm.
+#[cfg(feature = "std")]
+fn ln(x: f64) -> f64 {
+    x.ln()
+}
+
+/// The natural logarithm, which `core` does not provide.
+#[cfg(not(feature = "std"))]
+fn ln(x: f64) -> f64 {
+    // ln(x) = 2 atanh((x - 1) / (x + 1)), after scaling x into [1, 2)
+    let mut x = x;
+    let mut exponent = 0.0;
+    while x >= 2.0 {
+        x /= 2.0;
+        exponent += 1.0;
+    }
+    let y = (x - 1.0) / (x + 1.0);
+    let mut term = y;
+    let mut sum = 0.0f64;
+    let mut n = 1.0;
+    while term.abs() > f64::EPSILON * sum.abs() {
+        sum += term / n;
+        term *= y * y;
+        n += 2.0;
+    }
+    2.0 * sum + exponent * core::f64::consts::LN_2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsvBuilder, Options, Result};
+
+    fn sample() -> Dsv {
+        DsvBuilder::new()
+            .headers(["fruit", "price", "code"])
+            .record(["apples", "1.5", "10"])
+            .record(["pears", "", "007"])
+            .record(["kiwis", "4.5", "12"])
+            .record(["apples", "3", "9"])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn describe() {
+        let summary = sample().describe();
+        let fruit = &summary[0];
+        assert_eq!(fruit.name, "fruit");
+        assert_eq!((fruit.count, fruit.nulls, fruit.distinct), (4, 0, 3));
+        assert_eq!(fruit.min.as_deref(), Some("apples"));
+        assert_eq!(fruit.max.as_deref(), Some("pears"));
+        assert_eq!((fruit.min_length, fruit.max_length), (Some(5), Some(6)));
+        assert_eq!(fruit.top[0], ("apples".into(), 2));
+        assert_eq!(fruit.top[1], ("kiwis".into(), 1));
+        assert!(fruit.numeric.is_none());
+
+        let price = &summary[1];
+        assert_eq!((price.count, price.nulls), (3, 1));
+        let numeric = price.numeric.as_ref().unwrap();
+        assert_eq!((numeric.min, numeric.max, numeric.mean), (1.5, 4.5, 3.0));
+        assert_eq!(numeric.stddev, 1.5);
+        assert_eq!(numeric.quantiles, [(0.25, 2.25), (0.5, 3.0), (0.75, 3.75)]);
+
+        // numbers compare as numbers
+        let code = &summary[2];
+        assert_eq!(code.min.as_deref(), Some("007"));
+        assert_eq!(code.max.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn empty() {
+        let dsv = DsvBuilder::new()
+            .headers(["a"])
+            .record([""])
+            .build()
+            .unwrap();
+        let summary = dsv.describe();
+        assert_eq!((summary[0].count, summary[0].nulls), (0, 1));
+        assert_eq!(summary[0].min, None);
+        assert!(summary[0].top.is_empty());
+        assert!(summary[0].numeric.is_none());
+    }
+
+    #[test]
+    fn null_values() -> Result<()> {
+        let options = Options {
+            null_values: vec!["NA".into()],
+            ..Default::default()
+        };
+        let dsv = Dsv::from_str_with_options("1\nNA\n\"NA\"", options)?;
+        let summary = dsv.describe_with_options(DescribeOptions {
+            null_values: vec![],
+            ..Default::default()
+        });
+        assert_eq!((summary[0].count, summary[0].nulls), (2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn ragged() {
+        let mut describer = Describer::new(DescribeOptions::default());
+        describer.add_record(&["a", "b"]);
+        describer.add_record(&["c"]);
+        describer.add_record(&["d", "e", "f"]);
+        let counts: Vec<_> = describer
+            .finish()
+            .iter()
+            .map(|column| (column.count, column.nulls))
+            .collect();
+        assert_eq!(counts, [(3, 0), (2, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn streaming() {
+        let mut describer = Describer::new(DescribeOptions {
+            quantiles: vec![0.0, 0.5, 0.9, 1.0],
+            top: 2,
+            ..Default::default()
+        });
+        for i in 0..20_000u64 {
+            // a permutation of 0..20000
+            let value = (i * 7919) % 20_000;
+            describer.add_record(&[value.to_string(), (value % 3).to_string()]);
+        }
+        let summary = describer.finish();
+        assert_eq!(summary[0].name, "0");
+
+        let distinct = summary[0].distinct as f64;
+        assert!((distinct - 20_000.0).abs() < 20_000.0 * 0.05, "{distinct}");
+        let numeric = summary[0].numeric.as_ref().unwrap();
+        assert!((numeric.mean - 9_999.5).abs() < 1e-6);
+        assert!((numeric.stddev - 5_773.65).abs() < 1.0);
+        assert_eq!(numeric.quantiles[0], (0.0, 0.0));
+        assert_eq!(numeric.quantiles[3], (1.0, 19_999.0));
+        let median = numeric.quantiles[1].1;
+        assert!((median - 10_000.0).abs() < 200.0, "{median}");
+        let p90 = numeric.quantiles[2].1;
+        assert!((p90 - 18_000.0).abs() < 200.0, "{p90}");
+
+        assert_eq!(summary[1].distinct, 3);
+        assert_eq!(summary[1].top, [("0".into(), 6_667), ("1".into(), 6_667)]);
+    }
+
+    #[test]
+    fn math() {
+        assert_eq!(sqrt(2.25), 1.5);
+        assert!((ln(10.0) - core::f64::consts::LN_10).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,728 @@
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt, iter, ops::Range};
+
+use crate::{
+    column::Column,
+    error::{Error, Result},
+    Dsv,
+};
+
+/// The differences between two tables.
+///
+/// Displaying a diff produces a unified report with one hunk per record and
+/// one line per field, where removed values are prefixed with `-` and added
+/// values with `+`. As in a text diff, hunk headers number records from one.
+/// Null values are shown as `null`, and other values are quoted.
+///
+/// ```
+/// use dsv::{Dsv, Options};
+///
+/// let options = Options { headers: true, ..Default::default() };
+/// let old = Dsv::from_str_with_options("id,price\n1,1.5\n2,3\n", options.clone()).unwrap();
+/// let new = Dsv::from_str_with_options("id,price\n1,2\n3,4\n", options).unwrap();
+/// let diff = dsv::diff(&old, &new, &["id"]).unwrap();
+///
+/// assert_eq!(
+///     diff.to_string(),
+///     "@@ -1 +1 @@ id=\"1\"\n-price: \"1.5\"\n+price: \"2\"\n\
+///      @@ -2 @@ id=\"2\"\n-id: \"2\"\n-price: \"3\"\n\
+///      @@ +2 @@ id=\"3\"\n+id: \"3\"\n+price: \"4\"\n"
+/// );
+/// ```
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Diff {
+    /// The names of the key columns, or empty if records are compared by
+    /// order.
+    pub key_columns: Vec<String>,
+    /// The names of the columns that are only in the new table.
+    pub added_columns: Vec<String>,
+    /// The names of the columns that are only in the old table.
+    pub removed_columns: Vec<String>,
+    /// The records that differ. When matching by key, these follow the order
+    /// of the old table, with added records at the end. When comparing by
+    /// order, added records are placed where they appear in the new table.
+    pub records: Vec<RecordDiff>,
+}
+
+/// How a record differs between two tables.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiffKind {
+    /// The record is only in the new table.
+    Added,
+    /// The record is only in the old table.
+    Removed,
+    /// The record is in both tables with different values.
+    Changed,
+}
+
+/// A record that differs between two tables.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RecordDiff {
+    pub kind: DiffKind,
+    /// The index of the record in the old table, unless it was added.
+    pub old_record: Option<usize>,
+    /// The index of the record in the new table, unless it was removed.
+    pub new_record: Option<usize>,
+    /// The values of the key columns, with `None` for null fields, or empty if
+    /// records are compared by order.
+    pub key: Vec<Option<String>>,
+    /// Every field of an added or removed record, or the fields of a changed
+    /// record whose values differ.
+    pub fields: Vec<FieldDiff>,
+}
+
+/// A field that differs between two tables.
+///
+/// A field that is null in one table and empty in the other differs.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FieldDiff {
+    /// The header of the column, or its index if there are no headers.
+    pub column: String,
+    /// The value in the old table, with `None` for a null field, unless the
+    /// record was added.
+    pub old: Option<Option<String>>,
+    /// The value in the new table, with `None` for a null field, unless the
+    /// record was removed.
+    pub new: Option<Option<String>>,
+}
+
+/// Compare two tables, matching records by the values of one or more key
+/// columns.
+///
+/// If the tables have headers, columns are matched by name. Otherwise, they
+/// are matched by position. Key columns are resolved in both tables, so names
+/// must appear in both sets of headers. Only columns in both tables are
+/// compared, and columns in just one of them are listed as added or removed.
+/// Fails with `Error::Duplicate` if a key appears more than once in either
+/// table.
+pub fn diff<'c, C: Copy + Into<Column<'c>>>(
+    old: &Dsv,
+    new: &Dsv,
+    key_columns: &[C],
+) -> Result<Diff> {
+    let columns = Columns::new(old, new)?;
+    let old_keys = old.resolve_columns(key_columns)?;
+    let new_keys = new.resolve_columns(key_columns)?;
+
+    let new_lookup = lookup(new, &new_keys)?;
+    let mut matched = vec![false; new.records.len()];
+    let mut records = vec![];
+    // the old lookup is only needed to check for duplicates
+    lookup(old, &old_keys)?;
+    for ix in 0..old.records.len() {
+        let key = key(old, ix, &old_keys);
+        match new_lookup.get(&key) {
+            Some(&jx) => {
+                matched[jx] = true;
+                records.extend(columns.changed(old, ix, new, jx, owned(&key)));
+            }
+            None => records.push(columns.removed(old, ix, owned(&key))),
+        }
+    }
+    for (jx, _) in matched.iter().enumerate().filter(|(_, &matched)| !matched) {
+        records.push(columns.added(new, jx, owned(&key(new, jx, &new_keys))));
+    }
+
+    Ok(Diff {
+        key_columns: old_keys.iter().map(|&c| name(old, c)).collect(),
+        ..columns.finish(records)
+    })
+}
+
+/// Compare two tables that have no key by the order of their records.
+///
+/// Records are aligned by their longest common subsequence, as a text diff
+/// aligns lines. Within a run of records that do not align, removed and added
+/// records are paired in order as changed records. Columns are matched as with
+/// `diff`.
+pub fn diff_by_order(old: &Dsv, new: &Dsv) -> Result<Diff> {
+    let columns = Columns::new(old, new)?;
+    let equal = |ix: usize, jx: usize| {
+        (columns.pairs.iter()).all(|&(c, d)| old.field_opt(ix, c) == new.field_opt(jx, d))
+    };
+
+    let mut records = vec![];
+    let (mut removed, mut added) = (vec![], vec![]);
+    let mut flush = |removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        for (&ix, &jx) in removed.iter().zip(added.iter()) {
+            records.extend(columns.changed(old, ix, new, jx, vec![]));
+        }
+        let paired = removed.len().min(added.len());
+        for &ix in &removed[paired..] {
+            records.push(columns.removed(old, ix, vec![]));
+        }
+        for &jx in &added[paired..] {
+            records.push(columns.added(new, jx, vec![]));
+        }
+        removed.clear();
+        added.clear();
+    };
+    for edit in edits(old.records.len(), new.records.len(), equal) {
+        match edit {
+            Edit::Equal => flush(&mut removed, &mut added),
+            Edit::Remove(ix) => removed.push(ix),
+            Edit::Add(jx) => added.push(jx),
+        }
+    }
+    flush(&mut removed, &mut added);
+
+    Ok(columns.finish(records))
+}
+
+impl Diff {
+    /// Check whether the tables have the same columns and records.
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty() && self.removed_columns.is_empty() && self.records.is_empty()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for column in &self.removed_columns {
+            writeln!(f, "-column {column:?}")?;
+        }
+        for column in &self.added_columns {
+            writeln!(f, "+column {column:?}")?;
+        }
+        for record in &self.records {
+            write!(f, "@@")?;
+            if let Some(old) = record.old_record {
+                write!(f, " -{}", old + 1)?;
+            }
+            if let Some(new) = record.new_record {
+                write!(f, " +{}", new + 1)?;
+            }
+            write!(f, " @@")?;
+            for (ix, (column, value)) in self.key_columns.iter().zip(&record.key).enumerate() {
+                let separator = if ix == 0 { " " } else { ", " };
+                write!(f, "{separator}{column}={}", Value(value))?;
+            }
+            writeln!(f)?;
+            for field in &record.fields {
+                if let Some(old) = &field.old {
+                    writeln!(f, "-{}: {}", field.column, Value(old))?;
+                }
+                if let Some(new) = &field.new {
+                    writeln!(f, "+{}: {}", field.column, Value(new))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A value in a diff report, which is quoted unless it is null.
+struct Value<'a>(&'a Option<String>);
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value:?}"),
+            None => write!(f, "null"),
+        }
+    }
+}
+
+/// The columns of two tables, matched by name or position.
+struct Columns {
+    /// The compared columns in the old and new tables.
+    pairs: Vec<(usize, usize)>,
+    /// The names of the compared columns.
+    names: Vec<String>,
+    added: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl Columns {
+    fn new(old: &Dsv, new: &Dsv) -> Result<Columns> {
+        let names = |dsv: &Dsv| (0..dsv.width()).map(|c| name(dsv, c)).collect::<Vec<_>>();
+        let (old_names, new_names) = (names(old), names(new));
+        let pairs: Vec<(usize, usize)> = match (old.headers(), new.headers()) {
+            (Some(_), Some(_)) => old_names
+                .iter()
+                .enumerate()
+                .filter_map(|(c, name)| Some((c, new_names.iter().position(|n| n == name)?)))
+                .collect(),
+            (None, None) => (0..old.width().min(new.width())).map(|c| (c, c)).collect(),
+            _ => return Err(Error::Headers),
+        };
+        Ok(Columns {
+            names: pairs.iter().map(|&(c, _)| old_names[c].clone()).collect(),
+            added: (0..new.width())
+                .filter(|&d| !pairs.iter().any(|&(_, e)| e == d))
+                .map(|d| new_names[d].clone())
+                .collect(),
+            removed: (0..old.width())
+                .filter(|&c| !pairs.iter().any(|&(e, _)| e == c))
+                .map(|c| old_names[c].clone())
+                .collect(),
+            pairs,
+        })
+    }
+
+    fn changed(
+        &self,
+        old: &Dsv,
+        ix: usize,
+        new: &Dsv,
+        jx: usize,
+        key: Vec<Option<String>>,
+    ) -> Option<RecordDiff> {
+        let fields: Vec<FieldDiff> = self
+            .pairs
+            .iter()
+            .zip(&self.names)
+            .map(|(&(c, d), name)| (old.field_opt(ix, c), new.field_opt(jx, d), name))
+            .filter(|(a, b, _)| a != b)
+            .map(|(a, b, name)| FieldDiff {
+                column: name.clone(),
+                old: Some(a.map(String::from)),
+                new: Some(b.map(String::from)),
+            })
+            .collect();
+        if fields.is_empty() {
+            return None;
+        }
+        Some(RecordDiff {
+            kind: DiffKind::Changed,
+            old_record: Some(ix),
+            new_record: Some(jx),
+            key,
+            fields,
+        })
+    }
+
+    fn removed(&self, old: &Dsv, ix: usize, key: Vec<Option<String>>) -> RecordDiff {
+        let fields = (0..old.width())
+            .map(|c| FieldDiff {
+                column: name(old, c),
+                old: Some(old.field_opt(ix, c).map(String::from)),
+                new: None,
+            })
+            .collect();
+        RecordDiff {
+            kind: DiffKind::Removed,
+            old_record: Some(ix),
+            new_record: None,
+            key,
+            fields,
+        }
+    }
+
+    fn added(&self, new: &Dsv, jx: usize, key: Vec<Option<String>>) -> RecordDiff {
+        let fields = (0..new.width())
+            .map(|d| FieldDiff {
+                column: name(new, d),
+                old: None,
+                new: Some(new.field_opt(jx, d).map(String::from)),
+            })
+            .collect();
+        RecordDiff {
+            kind: DiffKind::Added,
+            old_record: None,
+            new_record: Some(jx),
+            key,
+            fields,
+        }
+    }
+
+    fn finish(self, records: Vec<RecordDiff>) -> Diff {
+        Diff {
+            key_columns: vec![],
+            added_columns: self.added,
+            removed_columns: self.removed,
+            records,
+        }
+    }
+}
+
+/// Get the header of a column, or its index if there are no headers.
+fn name(dsv: &Dsv, column: usize) -> String {
+    match dsv.headers() {
+        Some(headers) => headers[column].clone(),
+        None => column.to_string(),
+    }
+}
+
+/// Get the key of a record, with `None` for null fields.
+fn key<'a>(dsv: &'a Dsv, record: usize, keys: &[usize]) -> Vec<Option<&'a str>> {
+    keys.iter().map(|&c| dsv.field_opt(record, c)).collect()
+}
+
+fn owned(key: &[Option<&str>]) -> Vec<Option<String>> {
+    key.iter().map(|value| value.map(String::from)).collect()
+}
+
+/// Map each key of a table to its record, failing if a key is repeated.
+fn lookup<'a>(dsv: &'a Dsv, keys: &[usize]) -> Result<BTreeMap<Vec<Option<&'a str>>, usize>> {
+    let mut lookup = BTreeMap::new();
+    for ix in 0..dsv.records.len() {
+        if lookup.insert(key(dsv, ix, keys), ix).is_some() {
+            return Err(Error::Duplicate { record: ix });
+        }
+    }
+    Ok(lookup)
+}
+
+/// A step in transforming one sequence into another.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Edit {
+    Equal,
+    Remove(usize),
+    Add(usize),
+}
+
+/// Find the shortest edit script between two sequences with Myers' algorithm.
+///
+/// This is the linear space variant, which splits the sequences where an
+/// optimal path crosses its middle snake and recurses on both halves.
+fn edits(n: usize, m: usize, equal: impl Fn(usize, usize) -> bool) -> Vec<Edit> {
+    let mut script = Vec::with_capacity(n.max(m));
+    conquer(0..n, 0..m, &equal, &mut script);
+    script
+}
+
+/// Append the edit script between two ranges of the sequences.
+fn conquer<F: Fn(usize, usize) -> bool>(
+    mut old: Range<usize>,
+    mut new: Range<usize>,
+    equal: &F,
+    script: &mut Vec<Edit>,
+) {
+    // skip the common prefix and suffix, which are usually most of the input
+    while !old.is_empty() && !new.is_empty() && equal(old.start, new.start) {
+        script.push(Edit::Equal);
+        old.start += 1;
+        new.start += 1;
+    }
+    let mut suffix = 0;
+    while !old.is_empty() && !new.is_empty() && equal(old.end - 1, new.end - 1) {
+        suffix += 1;
+        old.end -= 1;
+        new.end -= 1;
+    }
+
+    let split = if old.is_empty() || new.is_empty() {
+        None
+    } else {
+        middle_snake(old.clone(), new.clone(), equal)
+    };
+    match split {
+        Some((x, y)) => {
+            conquer(old.start..x, new.start..y, equal, script);
+            conquer(x..old.end, y..new.end, equal, script);
+        }
+        None => {
+            script.extend(old.map(Edit::Remove));
+            script.extend(new.map(Edit::Add));
+        }
+    }
+    script.extend(iter::repeat_n(Edit::Equal, suffix));
+}
+
+/// Find a point on an optimal path between two ranges, at the end of the
+/// snake where paths searched from either end first overlap.
+///
+/// The ranges must not be empty, and must not start or end with equal
+/// elements, so that the point is neither the start nor the end.
+fn middle_snake<F: Fn(usize, usize) -> bool>(
+    old: Range<usize>,
+    new: Range<usize>,
+    equal: &F,
+) -> Option<(usize, usize)> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    // for each number of edits d, the furthest x reached on each diagonal
+    // k = x - y, from the start in `forward` and from the end in `backward`,
+    // or -1 for diagonals not reached
+    let max = (n + m + 1) / 2;
+    let offset = max;
+    let mut forward = vec![-1isize; 2 * max as usize + 2];
+    forward[offset as usize + 1] = 0;
+    let mut backward = forward.clone();
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+    // diagonals that have left the grid, trimmed from each end of the search
+    let (mut forward_start, mut forward_end) = (0, 0);
+    let (mut backward_start, mut backward_end) = (0, 0);
+    let ix = |k: isize| (offset + k) as usize;
+    for d in 0..max {
+        for k in (-d + forward_start..=d - forward_end).step_by(2) {
+            let mut x = if k == -d || (k != d && forward[ix(k - 1)] < forward[ix(k + 1)]) {
+                forward[ix(k + 1)]
+            } else {
+                forward[ix(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && equal(old.start + x as usize, new.start + y as usize) {
+                x += 1;
+                y += 1;
+            }
+            forward[ix(k)] = x;
+            if x > n {
+                forward_end += 2;
+            } else if y > m {
+                forward_start += 2;
+            } else if odd {
+                let reverse = backward.get(ix(delta - k)).copied().unwrap_or(-1);
+                if reverse != -1 && x >= n - reverse {
+                    return Some((old.start + x as usize, new.start + y as usize));
+                }
+            }
+        }
+        for k in (-d + backward_start..=d - backward_end).step_by(2) {
+            let mut x = if k == -d || (k != d && backward[ix(k - 1)] < backward[ix(k + 1)]) {
+                backward[ix(k + 1)]
+            } else {
+                backward[ix(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && equal(old.end - 1 - x as usize, new.end - 1 - y as usize) {
+                x += 1;
+                y += 1;
+            }
+            backward[ix(k)] = x;
+            if x > n {
+                backward_end += 2;
+            } else if y > m {
+                backward_start += 2;
+            } else if !odd {
+                let k = delta - k;
+                let reached = forward.get(ix(k)).copied().unwrap_or(-1);
+                if reached != -1 && reached >= n - x {
+                    let y = reached - k;
+                    return Some((old.start + reached as usize, new.start + y as usize));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+
+    fn read(text: &str) -> Result<Dsv> {
+        let options = Options {
+            headers: true,
+            ..Default::default()
+        };
+        Dsv::from_str_with_options(text, options)
+    }
+
+    fn kinds(diff: &Diff) -> Vec<(DiffKind, Option<usize>, Option<usize>)> {
+        diff.records
+            .iter()
+            .map(|record| (record.kind, record.old_record, record.new_record))
+            .collect()
+    }
+
+    #[test]
+    fn keys() -> Result<()> {
+        let old = read("id,name,price\n1,apples,1.5\n2,pears,3\n3,kiwis,1\n")?;
+        let new = read("id,price,name\n3,1,kiwis\n1,2,apples\n4,5,plums\n")?;
+        let diff = diff(&old, &new, &["id"])?;
+        assert_eq!(diff.key_columns, ["id"]);
+        assert_eq!(
+            kinds(&diff),
+            [
+                (DiffKind::Changed, Some(0), Some(1)),
+                (DiffKind::Removed, Some(1), None),
+                (DiffKind::Added, None, Some(2)),
+            ]
+        );
+        assert_eq!(diff.records[0].key, [Some("1".into())]);
+        assert_eq!(
+            diff.records[0].fields,
+            [FieldDiff {
+                column: "price".into(),
+                old: Some(Some("1.5".into())),
+                new: Some(Some("2".into())),
+            }]
+        );
+        assert_eq!(diff.records[2].fields.len(), 3);
+        assert_eq!(diff.records[2].fields[1].column, "price");
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let read = |text| {
+            let options = Options {
+                headers: true,
+                null_values: vec!["NA".into()],
+                ..Default::default()
+            };
+            Dsv::from_str_with_options(text, options)
+        };
+        let old = read("id,price\n1,NA\nNA,2\n,3\n")?;
+        let new = read("id,price\n1,\nNA,2\n,4\n")?;
+        let diff = diff(&old, &new, &["id"])?;
+        assert_eq!(
+            diff.to_string(),
+            "@@ -1 +1 @@ id=\"1\"\n-price: null\n+price: \"\"\n\
+             @@ -3 +3 @@ id=\"\"\n-price: \"3\"\n+price: \"4\"\n"
+        );
+        assert_eq!(diff.records[0].fields[0].old, Some(None));
+
+        let diff = diff_by_order(&old, &new)?;
+        assert_eq!(
+            kinds(&diff),
+            [
+                (DiffKind::Changed, Some(0), Some(0)),
+                (DiffKind::Changed, Some(2), Some(2)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn columns() -> Result<()> {
+        let old = read("id,qty\n1,2\n")?;
+        let new = read("id,discount\n1,5\n")?;
+        let diff = diff(&old, &new, &[0])?;
+        assert_eq!(diff.added_columns, ["discount"]);
+        assert_eq!(diff.removed_columns, ["qty"]);
+        assert!(diff.records.is_empty());
+        assert_eq!(diff.to_string(), "-column \"qty\"\n+column \"discount\"\n");
+
+        let same = super::diff(&old, &old, &["id"])?;
+        assert!(same.is_empty());
+        assert_eq!(same.to_string(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> Result<()> {
+        let old = read("id\n1\n1\n")?;
+        let new = read("id\n1\n")?;
+        assert!(matches!(
+            diff(&old, &new, &[0]),
+            Err(Error::Duplicate { record: 1 })
+        ));
+        assert!(matches!(
+            diff(&new, &read("id")?, &["name"]),
+            Err(Error::UnknownColumn(_))
+        ));
+        let mut headerless = Dsv::new();
+        headerless.push_record(vec!["1".into()])?;
+        assert!(matches!(
+            diff_by_order(&new, &headerless),
+            Err(Error::Headers)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn order() -> Result<()> {
+        let old = Dsv::from_str("a,1\nb,2\nc,3\nd,4\ne,5")?;
+        let new = Dsv::from_str("a,1\nx,0\nb,2\nc,9\ne,5\nf,6")?;
+        let diff = diff_by_order(&old, &new)?;
+        assert!(diff.key_columns.is_empty());
+        assert_eq!(
+            kinds(&diff),
+            [
+                (DiffKind::Added, None, Some(1)),
+                (DiffKind::Changed, Some(2), Some(3)),
+                (DiffKind::Removed, Some(3), None),
+                (DiffKind::Added, None, Some(5)),
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "@@ +2 @@\n+0: \"x\"\n+1: \"0\"\n\
+             @@ -3 +4 @@\n-1: \"3\"\n+1: \"9\"\n\
+             @@ -4 @@\n-0: \"d\"\n-1: \"4\"\n\
+             @@ +6 @@\n+0: \"f\"\n+1: \"6\"\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn edit_scripts() {
+        let script = |a: &str, b: &str| {
+            let (a, b) = (a.as_bytes(), b.as_bytes());
+            edits(a.len(), b.len(), |x, y| a[x] == b[y])
+        };
+        assert_eq!(script("", ""), []);
+        assert_eq!(script("ab", "ab"), [Edit::Equal, Edit::Equal]);
+        assert_eq!(script("", "ab"), [Edit::Add(0), Edit::Add(1)]);
+        assert_eq!(script("ab", ""), [Edit::Remove(0), Edit::Remove(1)]);
+        let edits = script("abcabba", "cbabac");
+        let equal = edits.iter().filter(|&&edit| edit == Edit::Equal).count();
+        assert_eq!((edits.len(), equal), (9, 4));
+
+        // every script transforms the old sequence into the new one
+        let inputs = ["", "a", "ab", "ba", "abcabba", "cbabac", "xaxbx", "abab"];
+        for a in inputs {
+            for b in inputs {
+                let (mut x, mut result) = (0, vec![]);
+                for edit in script(a, b) {
+                    match edit {
+                        Edit::Equal => {
+                            result.push(a.as_bytes()[x]);
+                            x += 1;
+                        }
+                        Edit::Remove(ix) => {
+                            assert_eq!(ix, x);
+                            x += 1;
+                        }
+                        Edit::Add(jx) => result.push(b.as_bytes()[jx]),
+                    }
+                }
+                assert_eq!((x, result.as_slice()), (a.len(), b.as_bytes()), "{a} {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn minimal_scripts() {
+        // every sequence of up to six elements from a two letter alphabet
+        let inputs: Vec<Vec<u8>> = (0..=6)
+            .flat_map(|len| (0..1 << len).map(move |bits| (len, bits)))
+            .map(|(len, bits)| (0..len).map(|i| b'a' + (bits >> i & 1) as u8).collect())
+            .collect();
+        for a in &inputs {
+            for b in &inputs {
+                let mut lcs = vec![vec![0; b.len() + 1]; a.len() + 1];
+                for x in (0..a.len()).rev() {
+                    for y in (0..b.len()).rev() {
+                        lcs[x][y] = if a[x] == b[y] {
+                            lcs[x + 1][y + 1] + 1
+                        } else {
+                            lcs[x + 1][y].max(lcs[x][y + 1])
+                        };
+                    }
+                }
+                let script = edits(a.len(), b.len(), |x, y| a[x] == b[y]);
+                let (mut x, mut y) = (0, 0);
+                for edit in &script {
+                    match *edit {
+                        Edit::Equal => {
+                            assert_eq!(a[x], b[y]);
+                            x += 1;
+                            y += 1;
+                        }
+                        Edit::Remove(ix) => {
+                            assert_eq!(ix, x);
+                            x += 1;
+                        }
+                        Edit::Add(jx) => {
+                            assert_eq!(jx, y);
+                            y += 1;
+                        }
+                    }
+                }
+                assert_eq!((x, y), (a.len(), b.len()));
+                let equal = script.iter().filter(|&&edit| edit == Edit::Equal).count();
+                assert_eq!(equal, lcs[0][0], "{a:?} {b:?}");
+            }
+        }
+    }
+}
@@ -1,26 +1,52 @@
-use std::ops::{Index, IndexMut};
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+use core::ops::{Index, IndexMut};
+#[cfg(feature = "std")]
+use std::path::Path;
 
 pub use crate::parser::Options;
-use crate::{error::Result, parser::Parser};
+use crate::{
+    column::{Column, Columns, Fields},
+    error::{Error, Result},
+    parser::Parser,
+};
 
 /// A table of delimiter-separated values.
 ///
 /// A `Dsv` is made up of records, and a record is made up of fields. Fields are
-/// stored as text and must be parsed into native data types separately.
-#[derive(Default, PartialEq, Debug)]
+/// stored as text and can be parsed into native data types on demand with
+/// `Record::get_as` or `Dsv::parse_column`. A table may also have headers that
+/// name its columns.
+///
+/// Every record contains the same number of fields, as do the headers. Methods
+/// that modify the table preserve this invariant.
+///
+/// Fields parsed from one of `Options::null_values` are null. A null field is
+/// stored as an empty string, and `Record::get_opt` returns `None` for it.
+/// Nulls move with their fields when records or columns are rearranged, and
+/// are carried into tables derived from this one. `Dsv::set_field` assigns a
+/// field, or makes it null, without affecting the rest of its record. Since a
+/// field assigned in place can not be told apart from one left unchanged,
+/// mutable access to a record through `IndexMut` makes all of its fields
+/// non-null.
+#[derive(Default, Debug)]
 pub struct Dsv {
+    pub(crate) headers: Option<Vec<String>>,
     pub(crate) records: Vec<Vec<String>>,
+    /// Flags marking the null fields of each record, or empty if no field has
+    /// been null.
+    pub(crate) nulls: Vec<Vec<bool>>,
 }
 
 impl Dsv {
-    /// Create a new Dsv.
-    pub(crate) fn new() -> Dsv {
+    /// Create a new, empty Dsv without headers.
+    pub fn new() -> Dsv {
         Dsv {
             ..Default::default()
         }
     }
 
     /// Parse a Dsv from an input string.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(text: &str) -> Result<Dsv> {
         Parser::from_str(text)
     }
@@ -30,14 +56,357 @@ impl Dsv {
         Parser::from_str_with_options(text, options)
     }
 
+    /// Parse a Dsv from the file at the specified path.
+    ///
+    /// The file is read into memory in full. To parse a large file without
+    /// copying it, map it with `Mmap::open` and parse it with a `Reader`.
+    /// Fails with `Error::Encoding` if the file is not valid UTF-8.
+    #[cfg(feature = "std")]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Dsv> {
+        Dsv::from_path_with_options(path, Options::default())
+    }
+
+    /// Parse a Dsv from the file at the specified path with options.
+    #[cfg(feature = "std")]
+    pub fn from_path_with_options<P: AsRef<Path>>(path: P, options: Options) -> Result<Dsv> {
+        let text = String::from_utf8(std::fs::read(path)?).map_err(|_| Error::Encoding)?;
+        Dsv::from_str_with_options(&text, options)
+    }
+
+    /// Add a parsed record, which becomes the headers if the table should have
+    /// headers and does not yet.
+    pub(crate) fn push_parsed(&mut self, record: Vec<Option<Cow<str>>>, headers: bool) {
+        let record: Vec<_> = record
+            .into_iter()
+            .map(|field| field.map(Cow::into_owned))
+            .collect();
+        if headers && self.headers.is_none() {
+            self.headers = Some(record.into_iter().map(Option::unwrap_or_default).collect());
+            return;
+        }
+        self.push_opt(record);
+    }
+
+    /// Create a table from records in which `None` marks a null field,
+    /// without checking their width.
+    pub(crate) fn from_opt(headers: Option<Vec<String>>, records: Vec<Vec<Option<String>>>) -> Dsv {
+        let mut dsv = Dsv {
+            headers,
+            ..Default::default()
+        };
+        for record in records {
+            dsv.push_opt(record);
+        }
+        dsv
+    }
+
+    /// Append a record in which `None` marks a null field.
+    ///
+    /// Fails if the record does not contain the same number of fields as the
+    /// rest of the table.
+    pub(crate) fn push_record_opt(&mut self, record: Vec<Option<String>>) -> Result<()> {
+        self.check_width(record.len())?;
+        self.push_opt(record);
+        Ok(())
+    }
+
+    /// Append a record in which `None` marks a null field, without checking
+    /// its width.
+    fn push_opt(&mut self, record: Vec<Option<String>>) {
+        let nulls: Vec<bool> = record.iter().map(Option::is_none).collect();
+        if !self.nulls.is_empty() || nulls.contains(&true) {
+            self.nulls
+                .resize(self.records.len(), vec![false; nulls.len()]);
+            self.nulls.push(nulls);
+        }
+        self.records
+            .push(record.into_iter().map(Option::unwrap_or_default).collect());
+    }
+
+    /// Check whether a field is null.
+    pub(crate) fn is_null(&self, record: usize, column: usize) -> bool {
+        self.nulls.get(record).is_some_and(|nulls| nulls[column])
+    }
+
+    /// Get a field, or `None` if it is null.
+    pub(crate) fn field_opt(&self, record: usize, column: usize) -> Option<&str> {
+        if self.is_null(record, column) {
+            return None;
+        }
+        Some(&self.records[record][column])
+    }
+
+    /// Get a copy of a record, with `None` in place of null fields.
+    pub(crate) fn record_opt(&self, record: usize) -> Vec<Option<String>> {
+        (0..self.records[record].len())
+            .map(|column| self.field_opt(record, column).map(String::from))
+            .collect()
+    }
+
+    /// Set the field in a column of a record, or make it null with `None`.
+    ///
+    /// Fails if there is no such record or column.
+    pub fn set_field(&mut self, record: usize, column: usize, value: Option<String>) -> Result<()> {
+        let field = self
+            .records
+            .get_mut(record)
+            .and_then(|fields| fields.get_mut(column))
+            .ok_or(Error::OutOfBounds)?;
+        *field = value.clone().unwrap_or_default();
+        if value.is_none() && self.nulls.is_empty() {
+            self.nulls = self
+                .records
+                .iter()
+                .map(|fields| vec![false; fields.len()])
+                .collect();
+        }
+        if let Some(nulls) = self.nulls.get_mut(record) {
+            nulls[column] = value.is_none();
+        }
+        Ok(())
+    }
+
+    /// Get the headers of this table, if it has any.
+    pub fn headers(&self) -> Option<&[String]> {
+        self.headers.as_deref()
+    }
+
     /// Get the records in this table.
     pub fn records(&self) -> &[Vec<String>] {
         &self.records
     }
+
+    /// Get the number of fields in each record.
+    pub fn width(&self) -> usize {
+        match &self.headers {
+            Some(headers) => headers.len(),
+            None => self.records.first().map_or(0, Vec::len),
+        }
+    }
+
+    /// Get the index of a column, given its index or header name.
+    ///
+    /// Fails if there is no such column.
+    pub fn column_index<'a, C: Into<Column<'a>>>(&self, column: C) -> Result<usize> {
+        column.into().resolve(self.headers(), self.width())
+    }
+
+    /// Get the indexes of several columns.
+    pub(crate) fn resolve_columns<'a, C: Copy + Into<Column<'a>>>(
+        &self,
+        columns: &[C],
+    ) -> Result<Vec<usize>> {
+        columns.iter().map(|&c| self.column_index(c)).collect()
+    }
+
+    /// Check whether the table contains no records and no headers, in which
+    /// case records of any width are accepted.
+    fn is_unshaped(&self) -> bool {
+        self.headers.is_none() && self.records.is_empty()
+    }
+
+    fn check_width(&self, len: usize) -> Result<()> {
+        if !self.is_unshaped() && len != self.width() {
+            return Err(Error::FieldCount {
+                expected: self.width(),
+                found: len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Append a record to the end of the table.
+    ///
+    /// Fails if the record does not contain the same number of fields as the
+    /// rest of the table.
+    pub fn push_record(&mut self, record: Vec<String>) -> Result<()> {
+        self.check_width(record.len())?;
+        if !self.nulls.is_empty() {
+            self.nulls.push(vec![false; record.len()]);
+        }
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// Insert a record at the specified index, shifting later records down.
+    ///
+    /// Fails if the index is greater than the number of records or the record
+    /// does not contain the same number of fields as the rest of the table.
+    pub fn insert_record(&mut self, index: usize, record: Vec<String>) -> Result<()> {
+        if index > self.records.len() {
+            return Err(Error::OutOfBounds);
+        }
+        self.check_width(record.len())?;
+        if !self.nulls.is_empty() {
+            self.nulls.insert(index, vec![false; record.len()]);
+        }
+        self.records.insert(index, record);
+        Ok(())
+    }
+
+    /// Remove and return the record at the specified index.
+    pub fn remove_record(&mut self, index: usize) -> Result<Vec<String>> {
+        if index >= self.records.len() {
+            return Err(Error::OutOfBounds);
+        }
+        if !self.nulls.is_empty() {
+            self.nulls.remove(index);
+        }
+        Ok(self.records.remove(index))
+    }
+
+    /// Keep only the records for which the predicate returns `true`.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&[String]) -> bool,
+    {
+        let retain: Vec<bool> = self
+            .records
+            .iter()
+            .map(|record| predicate(record))
+            .collect();
+        self.retain_indexes(&retain);
+    }
+
+    /// Keep only the records whose flag is set.
+    pub(crate) fn retain_indexes(&mut self, retain: &[bool]) {
+        let mut flags = retain.iter();
+        self.records.retain(|_| *flags.next().unwrap());
+        let mut flags = retain.iter();
+        self.nulls.retain(|_| *flags.next().unwrap());
+    }
+
+    /// Reorder the records so that the record at `order[ix]` moves to `ix`.
+    pub(crate) fn reorder(&mut self, order: &[usize]) {
+        let mut records: Vec<_> = self.records.drain(..).map(Some).collect();
+        self.records = order
+            .iter()
+            .map(|&ix| records[ix].take().unwrap())
+            .collect();
+        if !self.nulls.is_empty() {
+            self.nulls = order.iter().map(|&ix| self.nulls[ix].clone()).collect();
+        }
+    }
+
+    /// Insert a column at the specified index, shifting later columns right.
+    ///
+    /// The column must contain one field per record. A header must be provided
+    /// if and only if the table has headers.
+    pub fn insert_column(
+        &mut self,
+        index: usize,
+        header: Option<String>,
+        fields: Vec<String>,
+    ) -> Result<()> {
+        if index > self.width() {
+            return Err(Error::OutOfBounds);
+        }
+        if fields.len() != self.records.len() {
+            return Err(Error::FieldCount {
+                expected: self.records.len(),
+                found: fields.len(),
+            });
+        }
+        match (&mut self.headers, header) {
+            (Some(headers), Some(header)) => headers.insert(index, header),
+            (None, None) => {}
+            _ => return Err(Error::Headers),
+        }
+        for (record, field) in self.records.iter_mut().zip(fields) {
+            record.insert(index, field);
+        }
+        for nulls in &mut self.nulls {
+            nulls.insert(index, false);
+        }
+        Ok(())
+    }
+
+    /// Remove and return the fields in the column at the specified index.
+    ///
+    /// The header of the column, if any, is removed as well.
+    pub fn remove_column(&mut self, index: usize) -> Result<Vec<String>> {
+        if index >= self.width() {
+            return Err(Error::OutOfBounds);
+        }
+        if let Some(headers) = &mut self.headers {
+            headers.remove(index);
+        }
+        for nulls in &mut self.nulls {
+            nulls.remove(index);
+        }
+        Ok(self
+            .records
+            .iter_mut()
+            .map(|record| record.remove(index))
+            .collect())
+    }
+
+    /// Swap the columns at the specified indexes, along with their headers.
+    pub fn swap_columns(&mut self, a: usize, b: usize) -> Result<()> {
+        if a >= self.width() || b >= self.width() {
+            return Err(Error::OutOfBounds);
+        }
+        if let Some(headers) = &mut self.headers {
+            headers.swap(a, b);
+        }
+        for record in &mut self.records {
+            record.swap(a, b);
+        }
+        for nulls in &mut self.nulls {
+            nulls.swap(a, b);
+        }
+        Ok(())
+    }
+
+    /// Get the fields in the column at the specified index.
+    ///
+    /// Returns `None` if there is no column at the specified index.
+    pub fn column(&self, index: usize) -> Option<Fields<'_>> {
+        if index >= self.width() {
+            return None;
+        }
+        Some(Fields::new(self, index))
+    }
+
+    /// Get the columns in this table.
+    pub fn columns(&self) -> Columns<'_> {
+        Columns::new(self)
+    }
+
+    /// Create a new table containing only the specified columns, in order.
+    ///
+    /// Fails if there is no column at one of the specified indexes.
+    pub fn select(&self, columns: &[usize]) -> Result<Dsv> {
+        if columns.iter().any(|&c| c >= self.width()) {
+            return Err(Error::OutOfBounds);
+        }
+        let select = |record: &Vec<String>| columns.iter().map(|&c| record[c].clone()).collect();
+        Ok(Dsv {
+            headers: self.headers.as_ref().map(select),
+            records: self.records.iter().map(select).collect(),
+            nulls: self
+                .nulls
+                .iter()
+                .map(|nulls| columns.iter().map(|&c| nulls[c]).collect())
+                .collect(),
+        })
+    }
+}
+
+impl PartialEq for Dsv {
+    /// Tables are equal if they have the same headers, records, and nulls.
+    fn eq(&self, other: &Self) -> bool {
+        self.headers == other.headers
+            && self.records == other.records
+            && (0..self.records.len()).all(|record| {
+                (0..self.records[record].len())
+                    .all(|column| self.is_null(record, column) == other.is_null(record, column))
+            })
+    }
 }
 
 impl Index<usize> for Dsv {
-    type Output = Vec<String>;
+    type Output = [String];
 
     /// Returns a reference to the record at the specified index.
     ///
@@ -50,8 +419,207 @@ impl Index<usize> for Dsv {
 impl IndexMut<usize> for Dsv {
     /// Returns a mutable reference to the record at the specified index.
     ///
+    /// Fields can be modified in place, but not added or removed. Every field
+    /// of the record stops being null.
+    ///
     /// Panics if there is no record at the specified index.
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.records[index]
+        let record = &mut self.records[index];
+        if let Some(nulls) = self.nulls.get_mut(index) {
+            nulls.fill(false);
+        }
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortKey;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_path() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("dsv-path-{}.csv", std::process::id()));
+        std::fs::write(&path, "a,b\nc,d\n")?;
+        let dsv = Dsv::from_path(&path);
+        std::fs::write(&path, b"a,\xff\n")?;
+        let invalid = Dsv::from_path(&path);
+        std::fs::remove_file(&path)?;
+        assert_eq!(dsv?.records(), [["a", "b"], ["c", "d"]]);
+        assert!(matches!(invalid, Err(Error::Encoding)));
+        Ok(())
+    }
+
+    #[test]
+    fn column() -> Result<()> {
+        let dsv = Dsv::from_str("a,1\nb,2\nc,3")?;
+        assert_eq!(dsv.column(1).unwrap().collect::<Vec<_>>(), ["1", "2", "3"]);
+        assert!(dsv.column(2).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn columns() -> Result<()> {
+        let dsv = Dsv::from_str("a,1\nb,2")?;
+        let columns: Vec<Vec<_>> = dsv.columns().map(Iterator::collect).collect();
+        assert_eq!(columns, [["a", "b"], ["1", "2"]]);
+        Ok(())
+    }
+
+    fn with_headers(text: &str) -> Result<Dsv> {
+        let options = Options {
+            headers: true,
+            ..Default::default()
+        };
+        Dsv::from_str_with_options(text, options)
+    }
+
+    #[test]
+    fn push_record() -> Result<()> {
+        let mut dsv = Dsv::new();
+        dsv.push_record(vec!["a".into(), "1".into()])?;
+        dsv.push_record(vec!["b".into(), "2".into()])?;
+        assert_eq!(dsv, Dsv::from_str("a,1\nb,2")?);
+        assert!(matches!(
+            dsv.push_record(vec!["c".into()]),
+            Err(Error::FieldCount {
+                expected: 2,
+                found: 1
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn push_record_with_headers() -> Result<()> {
+        let mut dsv = with_headers("name,count")?;
+        assert!(dsv.push_record(vec!["a".into()]).is_err());
+        dsv.push_record(vec!["a".into(), "1".into()])?;
+        assert_eq!(dsv, with_headers("name,count\na,1")?);
+        Ok(())
+    }
+
+    #[test]
+    fn insert_and_remove_record() -> Result<()> {
+        let mut dsv = Dsv::from_str("a\nc")?;
+        dsv.insert_record(1, vec!["b".into()])?;
+        assert_eq!(dsv, Dsv::from_str("a\nb\nc")?);
+        assert!(matches!(
+            dsv.insert_record(4, vec!["d".into()]),
+            Err(Error::OutOfBounds)
+        ));
+        assert_eq!(dsv.remove_record(0)?, ["a"]);
+        assert!(matches!(dsv.remove_record(2), Err(Error::OutOfBounds)));
+        assert_eq!(dsv, Dsv::from_str("b\nc")?);
+        Ok(())
+    }
+
+    #[test]
+    fn retain() -> Result<()> {
+        let mut dsv = Dsv::from_str("a,1\nb,2\nc,3")?;
+        dsv.retain(|record| record[1] != "2");
+        assert_eq!(dsv, Dsv::from_str("a,1\nc,3")?);
+        Ok(())
+    }
+
+    #[test]
+    fn insert_column() -> Result<()> {
+        let mut dsv = with_headers("name\na\nb")?;
+        dsv.insert_column(1, Some("count".into()), vec!["1".into(), "2".into()])?;
+        assert_eq!(dsv, with_headers("name,count\na,1\nb,2")?);
+        assert!(matches!(
+            dsv.insert_column(0, None, vec!["x".into(), "y".into()]),
+            Err(Error::Headers)
+        ));
+        assert!(matches!(
+            dsv.insert_column(0, Some("x".into()), vec!["x".into()]),
+            Err(Error::FieldCount { .. })
+        ));
+        assert!(matches!(
+            dsv.insert_column(3, Some("x".into()), vec!["x".into(), "y".into()]),
+            Err(Error::OutOfBounds)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn remove_column() -> Result<()> {
+        let mut dsv = with_headers("name,count\na,1\nb,2")?;
+        assert_eq!(dsv.remove_column(0)?, ["a", "b"]);
+        assert_eq!(dsv, with_headers("count\n1\n2")?);
+        assert!(matches!(dsv.remove_column(1), Err(Error::OutOfBounds)));
+        Ok(())
+    }
+
+    #[test]
+    fn swap_columns() -> Result<()> {
+        let mut dsv = with_headers("name,count\na,1")?;
+        dsv.swap_columns(0, 1)?;
+        assert_eq!(dsv, with_headers("count,name\n1,a")?);
+        assert!(matches!(dsv.swap_columns(0, 2), Err(Error::OutOfBounds)));
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let options = Options {
+            null_values: vec!["".into()],
+            ..Default::default()
+        };
+        let parse = |text| Dsv::from_str_with_options(text, options.clone());
+        let mut dsv = parse("c,\nb,\"\"\n,a")?;
+        assert_ne!(dsv, Dsv::from_str("c,\nb,\"\"\n,a")?);
+
+        dsv.insert_record(0, vec!["d".into(), "".into()])?;
+        dsv.sort_by_columns(&[SortKey::new(0)])?;
+        assert_eq!(dsv, parse(",a\nb,\"\"\nc,\nd,\"\"")?);
+        dsv.swap_columns(0, 1)?;
+        dsv.retain(|record| record[1] != "b");
+        assert_eq!(dsv, parse("a,\n,c\n\"\",d")?);
+
+        dsv.remove_column(1)?;
+        dsv[0][0] = "x".into();
+        let fields: Vec<_> = dsv.iter().map(|r| r.fields_opt()[0]).collect();
+        assert_eq!(fields, [Some("x"), None, Some("")]);
+        assert_eq!(dsv.select(&[0])?.record(1).unwrap().get_opt(0)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn reassigned_nulls() -> Result<()> {
+        fn fields(dsv: &Dsv, record: usize) -> Vec<Option<&str>> {
+            dsv.record(record).unwrap().fields_opt()
+        }
+        let options = Options {
+            null_values: vec!["".into()],
+            ..Default::default()
+        };
+        let mut dsv = Dsv::from_str_with_options("a,,\nb,,", options)?;
+
+        dsv[0][1] = "x".into();
+        dsv[0][1] = "".into();
+        assert_eq!(fields(&dsv, 0), [Some("a"), Some(""), Some("")]);
+
+        dsv.set_field(1, 1, Some("x".into()))?;
+        dsv.set_field(1, 1, Some("".into()))?;
+        assert_eq!(fields(&dsv, 1), [Some("b"), Some(""), None]);
+        dsv.set_field(1, 0, None)?;
+        assert_eq!(fields(&dsv, 1), [None, Some(""), None]);
+        assert!(matches!(dsv.set_field(1, 3, None), Err(Error::OutOfBounds)));
+
+        let mut dsv = Dsv::from_str("a,b")?;
+        dsv.set_field(0, 1, None)?;
+        assert_eq!(fields(&dsv, 0), [Some("a"), None]);
+        Ok(())
+    }
+
+    #[test]
+    fn select() -> Result<()> {
+        let dsv = Dsv::from_str("a,1,x\nb,2,y")?;
+        let selected = dsv.select(&[2, 0])?;
+        assert_eq!(selected, Dsv::from_str("x,a\ny,b")?);
+        assert!(matches!(dsv.select(&[3]), Err(Error::OutOfBounds)));
+        Ok(())
     }
 }
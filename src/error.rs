@@ -1,8 +1,59 @@
+use alloc::string::String;
+
 /// Error type for the dsv crate.
 #[derive(Debug)]
 pub enum Error {
     Parse,
+    /// A record or column was requested that does not exist.
+    OutOfBounds,
+    /// A column was referred to by a name that is not in the headers.
+    UnknownColumn(String),
+    /// A record index does not match the text it is used with.
+    IndexMismatch,
+    /// A record does not contain the expected number of fields.
+    FieldCount {
+        expected: usize,
+        found: usize,
+    },
+    /// Headers were provided for a table without headers, or vice versa.
+    Headers,
+    /// A value can not be written with the configured options, or converted to
+    /// another type.
+    Unrepresentable,
+    /// The input is not valid UTF-8.
+    Encoding,
+    /// A caller-provided buffer is too small to hold the output.
+    BufferTooSmall,
+    /// A field could not be parsed as the requested type.
+    Value {
+        record: usize,
+        column: usize,
+        value: String,
+    },
+    /// A record duplicates the key of an earlier record.
+    Duplicate {
+        record: usize,
+    },
+    /// A filter expression or pattern is not valid.
+    Expression(String),
+    /// Schema metadata is not valid or not supported.
+    Metadata(String),
+    /// A patch is not valid.
+    Patch(String),
+    /// A patch operation does not match the table it is applied to.
+    Conflict {
+        operation: usize,
+    },
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
 }
 
 /// Result type for the dsv crate.
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,525 @@
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+
+use crate::{
+    column::Column,
+    error::{Error, Result},
+    Dsv,
+};
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    /// A column referred to by header name.
+    Name(String),
+    /// A column referred to by index, such as `$0`.
+    Index(usize),
+    Number(f64),
+    Str(String),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    Is,
+    Empty,
+    LParen,
+    RParen,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Match,
+    NotMatch,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let bytes = text.as_bytes();
+    let mut tokens = vec![];
+    let mut ix = 0;
+    while ix < bytes.len() {
+        let rest = &text[ix..];
+        let current = bytes[ix];
+        if current.is_ascii_whitespace() {
+            ix += 1;
+            continue;
+        }
+
+        let two = rest.get(..2).unwrap_or("");
+        let (token, len) = match two {
+            "&&" => (Token::And, 2),
+            "||" => (Token::Or, 2),
+            "==" => (Token::Op(Op::Eq), 2),
+            "!=" => (Token::Op(Op::Ne), 2),
+            "<=" => (Token::Op(Op::Le), 2),
+            ">=" => (Token::Op(Op::Ge), 2),
+            "=~" => (Token::Op(Op::Match), 2),
+            "!~" => (Token::Op(Op::NotMatch), 2),
+            _ => match current {
+                b'<' => (Token::Op(Op::Lt), 1),
+                b'>' => (Token::Op(Op::Gt), 1),
+                b'!' => (Token::Not, 1),
+                b'(' => (Token::LParen, 1),
+                b')' => (Token::RParen, 1),
+                b'\'' | b'"' => scan_string(rest, ix)?,
+                b'`' => {
+                    let len = rest[1..]
+                        .find('`')
+                        .ok_or_else(|| error(ix, "unterminated column name"))?;
+                    (Token::Name(rest[1..1 + len].into()), len + 2)
+                }
+                b'$' => {
+                    let len = scan_while(&rest[1..], |c| c.is_ascii_digit());
+                    let index = rest[1..1 + len]
+                        .parse()
+                        .map_err(|_| error(ix, "expected a column index after `$`"))?;
+                    (Token::Index(index), len + 1)
+                }
+                b'0'..=b'9' | b'-' | b'.' => {
+                    let len = scan_while(&rest[1..], |c| {
+                        c.is_ascii_alphanumeric() || c == '.' || c == '+' || c == '-'
+                    }) + 1;
+                    let number = rest[..len]
+                        .parse()
+                        .map_err(|_| error(ix, "invalid number"))?;
+                    (Token::Number(number), len)
+                }
+                c if c == b'_' || c.is_ascii_alphabetic() => {
+                    let len = scan_while(rest, |c| c == '_' || c.is_ascii_alphanumeric());
+                    let token = match &rest[..len] {
+                        "is" => Token::Is,
+                        "not" => Token::Not,
+                        "empty" => Token::Empty,
+                        name => Token::Name(name.into()),
+                    };
+                    (token, len)
+                }
+                _ => return Err(error(ix, "unexpected character")),
+            },
+        };
+        tokens.push(token);
+        ix += len;
+    }
+    Ok(tokens)
+}
+
+fn scan_while(text: &str, predicate: impl Fn(char) -> bool) -> usize {
+    text.find(|c| !predicate(c)).unwrap_or(text.len())
+}
+
+/// Scan a string literal, in which a backslash escapes the following character.
+fn scan_string(text: &str, pos: usize) -> Result<(Token, usize)> {
+    let mut chars = text.char_indices();
+    let (_, quote) = chars.next().unwrap();
+    let mut value = String::new();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, c)) => value.push(c),
+                None => break,
+            },
+            c if c == quote => return Ok((Token::Str(value), ix + 1)),
+            c => value.push(c),
+        }
+    }
+    Err(error(pos, "unterminated string"))
+}
+
+fn error(pos: usize, message: &str) -> Error {
+    Error::Expression(format!("{message} at position {pos}"))
+}
+
+#[derive(Debug)]
+enum Operand {
+    Column(usize),
+    Number(f64),
+    Str(String),
+}
+
+impl Operand {
+    /// The text of the operand. A column that is missing from a short record
+    /// is empty.
+    fn text<'a>(&'a self, record: &'a [String]) -> Option<&'a str> {
+        match self {
+            Operand::Column(column) => Some(record.get(*column).map_or("", String::as_str)),
+            Operand::Str(value) => Some(value),
+            Operand::Number(_) => None,
+        }
+    }
+
+    fn number(&self, record: &[String]) -> Option<f64> {
+        match self {
+            Operand::Column(column) => record.get(*column)?.trim().parse().ok(),
+            Operand::Number(value) => Some(*value),
+            Operand::Str(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, Op, Operand),
+    #[cfg(feature = "regex")]
+    Match(Operand, regex::Regex),
+    IsEmpty(Operand),
+}
+
+impl Expr {
+    fn eval(&self, record: &[String]) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(record) && b.eval(record),
+            Expr::Or(a, b) => a.eval(record) || b.eval(record),
+            Expr::Not(a) => !a.eval(record),
+            Expr::Compare(a, op, b) => compare(a, *op, b, record),
+            #[cfg(feature = "regex")]
+            Expr::Match(a, regex) => match a.text(record) {
+                Some(text) => regex.is_match(text),
+                None => false,
+            },
+            Expr::IsEmpty(a) => a.text(record).is_some_and(str::is_empty),
+        }
+    }
+}
+
+/// Compare two operands, numerically if either is a number literal or both
+/// are numeric fields, and as text otherwise.
+fn compare(a: &Operand, op: Op, b: &Operand, record: &[String]) -> bool {
+    let literal = matches!(a, Operand::Number(_)) || matches!(b, Operand::Number(_));
+    let ordering = match (a.number(record), b.number(record)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y),
+        // a field that is not a number never matches a number literal
+        _ if literal => None,
+        _ => Some(
+            a.text(record)
+                .unwrap_or("")
+                .cmp(b.text(record).unwrap_or("")),
+        ),
+    };
+    match (op, ordering) {
+        (Op::Ne, None) => true,
+        (_, None) => false,
+        (Op::Eq, Some(o)) => o.is_eq(),
+        (Op::Ne, Some(o)) => o.is_ne(),
+        (Op::Lt, Some(o)) => o.is_lt(),
+        (Op::Le, Some(o)) => o.is_le(),
+        (Op::Gt, Some(o)) => o.is_gt(),
+        (Op::Ge, Some(o)) => o.is_ge(),
+        (Op::Match | Op::NotMatch, _) => unreachable!(),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    headers: Option<&'a [String]>,
+    width: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, message: &str) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(self.error(message)),
+        }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::Expression(format!("{message} at token {}", self.pos))
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.predicate()
+    }
+
+    fn predicate(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::LParen) {
+            self.next();
+            let expr = self.or()?;
+            self.expect(Token::RParen, "expected `)`")?;
+            return Ok(expr);
+        }
+
+        let left = self.operand()?;
+        match self.next() {
+            Some(Token::Is) => {
+                let negated = self.peek() == Some(&Token::Not);
+                if negated {
+                    self.next();
+                }
+                self.expect(Token::Empty, "expected `empty`")?;
+                let expr = Expr::IsEmpty(left);
+                Ok(if negated {
+                    Expr::Not(Box::new(expr))
+                } else {
+                    expr
+                })
+            }
+            Some(Token::Op(op @ (Op::Match | Op::NotMatch))) => {
+                let expr = self.regex(left)?;
+                Ok(if op == Op::NotMatch {
+                    Expr::Not(Box::new(expr))
+                } else {
+                    expr
+                })
+            }
+            Some(Token::Op(op)) => Ok(Expr::Compare(left, op, self.operand()?)),
+            _ => Err(self.error("expected a comparison")),
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    fn regex(&mut self, left: Operand) -> Result<Expr> {
+        let Some(Token::Str(pattern)) = self.next() else {
+            return Err(self.error("expected a pattern"));
+        };
+        let regex = regex::Regex::new(&pattern).map_err(|e| Error::Expression(format!("{e}")))?;
+        Ok(Expr::Match(left, regex))
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn regex(&mut self, _left: Operand) -> Result<Expr> {
+        Err(self.error("pattern matching requires the `regex` feature"))
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        let column = match self.next() {
+            Some(Token::Name(name)) => Column::Name(&name).resolve(self.headers, self.width)?,
+            Some(Token::Index(index)) => Column::Index(index).resolve(self.headers, self.width)?,
+            Some(Token::Number(value)) => return Ok(Operand::Number(value)),
+            Some(Token::Str(value)) => return Ok(Operand::Str(value)),
+            _ => return Err(self.error("expected a column or value")),
+        };
+        Ok(Operand::Column(column))
+    }
+}
+
+/// A compiled filter expression that selects records.
+///
+/// Expressions compare columns, referred to by header name or by index (such
+/// as `$0`), with numbers, strings, and other columns:
+///
+/// * `price > 10`: Numeric comparison. Fields that are not numbers never match.
+/// * `country == 'DE'`: String comparison. Strings can use single or double
+///   quotes, and a backslash escapes the following character.
+/// * `` `unit price` <= $2 ``: Columns with other characters in their names are
+///   surrounded by backticks. Two columns are compared numerically if both
+///   fields are numbers and as strings otherwise.
+/// * `name =~ '^A'` and `name !~ '^A'`: Regular expression matching, which
+///   requires the `regex` feature.
+/// * `notes is empty` and `notes is not empty`.
+///
+/// Predicates can be combined with `&&`, `||`, `!`, and parentheses.
+#[derive(Debug)]
+pub struct Filter {
+    expr: Expr,
+}
+
+impl Filter {
+    /// Compile an expression, resolving column names against the headers.
+    ///
+    /// Columns are checked against `width`, the number of fields in each
+    /// record.
+    pub fn new(expr: &str, headers: Option<&[String]>, width: usize) -> Result<Filter> {
+        let mut parser = Parser {
+            tokens: tokenize(expr)?,
+            pos: 0,
+            headers,
+            width,
+        };
+        let expr = parser.or()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected token"));
+        }
+        Ok(Filter { expr })
+    }
+
+    /// Check whether a record matches the filter.
+    ///
+    /// Columns that are missing from a record with fewer than `width` fields
+    /// are treated as empty.
+    pub fn matches(&self, record: &[String]) -> bool {
+        self.expr.eval(record)
+    }
+}
+
+impl Dsv {
+    /// Create a new table containing only the records matching an expression.
+    ///
+    /// See `Filter` for the expression syntax.
+    pub fn filter(&self, expr: &str) -> Result<Dsv> {
+        let filter = Filter::new(expr, self.headers(), self.width())?;
+        let matches: Vec<bool> = self.records.iter().map(|r| filter.matches(r)).collect();
+        let keep = |ix: &usize| matches[*ix];
+        Ok(Dsv {
+            headers: self.headers.clone(),
+            records: (0..self.records.len())
+                .filter(keep)
+                .map(|ix| self.records[ix].clone())
+                .collect(),
+            nulls: (0..self.nulls.len())
+                .filter(keep)
+                .map(|ix| self.nulls[ix].clone())
+                .collect(),
+        })
+    }
+
+    /// Iterate over the records matching an expression.
+    ///
+    /// See `Filter` for the expression syntax.
+    pub fn filter_records(&self, expr: &str) -> Result<impl Iterator<Item = &[String]>> {
+        let filter = Filter::new(expr, self.headers(), self.width())?;
+        Ok(self
+            .records
+            .iter()
+            .map(Vec::as_slice)
+            .filter(move |record| filter.matches(record)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DsvBuilder;
+
+    fn products() -> Dsv {
+        DsvBuilder::new()
+            .headers(["name", "price", "country", "unit price"])
+            .record(["Apple", "12", "DE", "1"])
+            .record(["Banana", "8", "DE", "2"])
+            .record(["Cherry", "15", "FR", ""])
+            .record(["Durian", "n/a", "DE", "20"])
+            .build()
+            .unwrap()
+    }
+
+    fn names(dsv: &Dsv, expr: &str) -> Result<Vec<String>> {
+        let filtered = dsv.filter(expr)?;
+        Ok(filtered.column(0).unwrap().map(String::from).collect())
+    }
+
+    #[test]
+    fn numeric_and_string() -> Result<()> {
+        let dsv = products();
+        assert_eq!(names(&dsv, "price > 10 && country == 'DE'")?, ["Apple"]);
+        assert_eq!(
+            names(&dsv, "price >= 8 && price < 15")?,
+            ["Apple", "Banana"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn not_a_number() -> Result<()> {
+        let dsv = products();
+        assert_eq!(names(&dsv, "price != 12")?, ["Banana", "Cherry", "Durian"]);
+        assert_eq!(names(&dsv, "price == 'n/a'")?, ["Durian"]);
+        Ok(())
+    }
+
+    #[test]
+    fn boolean_logic() -> Result<()> {
+        let dsv = products();
+        let expr = "!(country == \"DE\") || (name == 'Banana' && not price > 10)";
+        assert_eq!(names(&dsv, expr)?, ["Banana", "Cherry"]);
+        Ok(())
+    }
+
+    #[test]
+    fn is_empty() -> Result<()> {
+        let dsv = products();
+        assert_eq!(names(&dsv, "`unit price` is empty")?, ["Cherry"]);
+        assert_eq!(
+            names(&dsv, "$3 is not empty && $3 < $1")?,
+            ["Apple", "Banana", "Durian"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn regex() -> Result<()> {
+        let dsv = products();
+        assert_eq!(names(&dsv, "name =~ '^[AB]'")?, ["Apple", "Banana"]);
+        assert_eq!(names(&dsv, "name !~ 'an'")?, ["Apple", "Cherry"]);
+        Ok(())
+    }
+
+    #[test]
+    fn filter_records() -> Result<()> {
+        let dsv = products();
+        let records: Vec<_> = dsv.filter_records("country == 'FR'")?.collect();
+        assert_eq!(records, [&dsv[2]]);
+        Ok(())
+    }
+
+    #[test]
+    fn short_records() -> Result<()> {
+        let headers = ["name", "price", "notes"].map(String::from);
+        let record = ["apples".to_string()];
+        let matches = |expr| Filter::new(expr, Some(&headers), 3).map(|f| f.matches(&record));
+        assert!(matches("notes is empty")?);
+        assert!(matches("notes == ''")?);
+        assert!(!matches("price > 1")?);
+        assert!(matches("price != 1")?);
+        assert!(matches("name == 'apples'")?);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let dsv = products();
+        assert!(matches!(
+            dsv.filter("weight > 1"),
+            Err(Error::UnknownColumn(name)) if name == "weight"
+        ));
+        assert!(matches!(dsv.filter("$9 > 1"), Err(Error::OutOfBounds)));
+        assert!(matches!(dsv.filter("price >"), Err(Error::Expression(_))));
+        assert!(matches!(
+            dsv.filter("price > 1 )"),
+            Err(Error::Expression(_))
+        ));
+        assert!(matches!(
+            dsv.filter("name == 'x"),
+            Err(Error::Expression(_))
+        ));
+        assert!(matches!(dsv.filter("name"), Err(Error::Expression(_))));
+    }
+}
@@ -0,0 +1,327 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    column::Column,
+    error::{Error, Result},
+    Dsv,
+};
+
+/// A summary of a column computed over each group of records.
+///
+/// Numeric aggregates parse fields as floating-point numbers when they are
+/// computed. Empty fields are skipped, and any other field that is not a
+/// number results in `Error::Value`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Aggregate<'a> {
+    /// The number of records in the group.
+    Count,
+    /// The sum of a numeric column.
+    Sum(Column<'a>),
+    /// The arithmetic mean of a numeric column.
+    Mean(Column<'a>),
+    /// The smallest value in a numeric column.
+    Min(Column<'a>),
+    /// The largest value in a numeric column.
+    Max(Column<'a>),
+    /// The number of distinct values in a column, where null is a value of
+    /// its own.
+    DistinctCount(Column<'a>),
+}
+
+impl<'a> Aggregate<'a> {
+    /// Count the records in each group.
+    pub fn count() -> Aggregate<'a> {
+        Aggregate::Count
+    }
+
+    /// Sum a column.
+    pub fn sum<C: Into<Column<'a>>>(column: C) -> Aggregate<'a> {
+        Aggregate::Sum(column.into())
+    }
+
+    /// Average a column.
+    pub fn mean<C: Into<Column<'a>>>(column: C) -> Aggregate<'a> {
+        Aggregate::Mean(column.into())
+    }
+
+    /// Find the minimum of a column.
+    pub fn min<C: Into<Column<'a>>>(column: C) -> Aggregate<'a> {
+        Aggregate::Min(column.into())
+    }
+
+    /// Find the maximum of a column.
+    pub fn max<C: Into<Column<'a>>>(column: C) -> Aggregate<'a> {
+        Aggregate::Max(column.into())
+    }
+
+    /// Count the distinct values of a column.
+    pub fn distinct_count<C: Into<Column<'a>>>(column: C) -> Aggregate<'a> {
+        Aggregate::DistinctCount(column.into())
+    }
+
+    fn column(&self) -> Option<Column<'a>> {
+        match *self {
+            Aggregate::Count => None,
+            Aggregate::Sum(column)
+            | Aggregate::Mean(column)
+            | Aggregate::Min(column)
+            | Aggregate::Max(column)
+            | Aggregate::DistinctCount(column) => Some(column),
+        }
+    }
+
+    /// Get the header of the output column, such as `sum(price)`.
+    fn header(&self, headers: &[String], column: usize) -> String {
+        let name = match self {
+            Aggregate::Count => return "count".into(),
+            Aggregate::Sum(_) => "sum",
+            Aggregate::Mean(_) => "mean",
+            Aggregate::Min(_) => "min",
+            Aggregate::Max(_) => "max",
+            Aggregate::DistinctCount(_) => "distinct_count",
+        };
+        format!("{name}({})", headers[column])
+    }
+
+    /// Compute the aggregate over the specified records of a table.
+    fn compute(&self, dsv: &Dsv, column: usize, records: &[usize]) -> Result<String> {
+        let fields = records.iter().map(|&record| dsv.field_opt(record, column));
+        let numbers = || {
+            records.iter().filter_map(move |&record| {
+                let field = &dsv.records[record][column];
+                if field.trim().is_empty() {
+                    return None;
+                }
+                Some(field.trim().parse::<f64>().map_err(|_| Error::Value {
+                    record,
+                    column,
+                    value: field.clone(),
+                }))
+            })
+        };
+
+        let value = match self {
+            Aggregate::Count => records.len().to_string(),
+            Aggregate::Sum(_) => numbers().sum::<Result<f64>>()?.to_string(),
+            Aggregate::Mean(_) => {
+                let (sum, count) = numbers()
+                    .try_fold((0.0, 0), |(sum, count), x| x.map(|x| (sum + x, count + 1)))?;
+                match count {
+                    0 => String::new(),
+                    _ => (sum / count as f64).to_string(),
+                }
+            }
+            Aggregate::Min(_) | Aggregate::Max(_) => {
+                let is_min = matches!(self, Aggregate::Min(_));
+                let mut best: Option<f64> = None;
+                for x in numbers() {
+                    let x = x?;
+                    best = Some(match best {
+                        Some(b) if is_min => b.min(x),
+                        Some(b) => b.max(x),
+                        None => x,
+                    });
+                }
+                best.map(|b| b.to_string()).unwrap_or_default()
+            }
+            Aggregate::DistinctCount(_) => fields.collect::<BTreeSet<_>>().len().to_string(),
+        };
+        Ok(value)
+    }
+}
+
+/// Records of a table grouped by the values of one or more columns.
+///
+/// Created by `Dsv::group_by`. Groups are kept in the order in which their
+/// first record appears.
+pub struct GroupBy<'a> {
+    dsv: &'a Dsv,
+    columns: Vec<usize>,
+    /// The key of each group, with `None` for null fields, and its records.
+    groups: Vec<(Vec<Option<&'a str>>, Vec<usize>)>,
+}
+
+impl GroupBy<'_> {
+    /// Get the number of groups.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Check whether there are no groups.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Compute aggregates over each group.
+    ///
+    /// The result contains a record per group, made up of the grouped columns
+    /// followed by each aggregate. If the table has headers, the result does
+    /// too, with aggregates named like `sum(price)`.
+    pub fn aggregate(&self, aggregates: &[Aggregate]) -> Result<Dsv> {
+        let dsv = self.dsv;
+        let columns = aggregates
+            .iter()
+            .map(|aggregate| match aggregate.column() {
+                Some(column) => column.resolve(dsv.headers(), dsv.width()),
+                None => Ok(0),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let headers = dsv.headers().map(|headers| {
+            let keys = self.columns.iter().map(|&c| headers[c].clone());
+            let aggregates = aggregates
+                .iter()
+                .zip(&columns)
+                .map(|(aggregate, &c)| aggregate.header(headers, c));
+            keys.chain(aggregates).collect()
+        });
+
+        let mut records = vec![];
+        for (key, group) in &self.groups {
+            let mut record: Vec<Option<String>> = key.iter().map(|k| k.map(String::from)).collect();
+            for (aggregate, &column) in aggregates.iter().zip(&columns) {
+                record.push(Some(aggregate.compute(dsv, column, group)?));
+            }
+            records.push(record);
+        }
+
+        Ok(Dsv::from_opt(headers, records))
+    }
+}
+
+impl Dsv {
+    /// Group the records by the values of one or more columns.
+    ///
+    /// Null fields are grouped separately from empty ones.
+    pub fn group_by<'c, C: Copy + Into<Column<'c>>>(&self, columns: &[C]) -> Result<GroupBy<'_>> {
+        let columns = self.resolve_columns(columns)?;
+
+        let mut lookup = BTreeMap::new();
+        let mut groups: Vec<(Vec<Option<&str>>, Vec<usize>)> = vec![];
+        for ix in 0..self.records.len() {
+            let key: Vec<_> = columns.iter().map(|&c| self.field_opt(ix, c)).collect();
+            let group = *lookup.entry(key.clone()).or_insert_with(|| {
+                groups.push((key, vec![]));
+                groups.len() - 1
+            });
+            groups[group].1.push(ix);
+        }
+
+        Ok(GroupBy {
+            dsv: self,
+            columns,
+            groups,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsvBuilder, Options};
+
+    fn sales() -> Dsv {
+        DsvBuilder::new()
+            .headers(["country", "product", "price"])
+            .record(["DE", "apple", "10"])
+            .record(["FR", "apple", "4.5"])
+            .record(["DE", "pear", "20"])
+            .record(["DE", "apple", ""])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn aggregate() -> Result<()> {
+        let summary = sales().group_by(&["country"])?.aggregate(&[
+            Aggregate::count(),
+            Aggregate::sum("price"),
+            Aggregate::mean("price"),
+            Aggregate::min("price"),
+            Aggregate::max("price"),
+            Aggregate::distinct_count("product"),
+        ])?;
+        assert_eq!(
+            summary.headers().unwrap(),
+            [
+                "country",
+                "count",
+                "sum(price)",
+                "mean(price)",
+                "min(price)",
+                "max(price)",
+                "distinct_count(product)"
+            ]
+        );
+        assert_eq!(summary[0], ["DE", "3", "30", "15", "10", "20", "2"]);
+        assert_eq!(summary[1], ["FR", "1", "4.5", "4.5", "4.5", "4.5", "1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_columns() -> Result<()> {
+        let dsv = sales();
+        let groups = dsv.group_by(&[0, 1])?;
+        assert_eq!(groups.len(), 3);
+        let summary = groups.aggregate(&[Aggregate::count()])?;
+        assert_eq!(summary[0], ["DE", "apple", "2"]);
+        assert_eq!(summary[2], ["DE", "pear", "1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn no_headers() -> Result<()> {
+        let dsv = Dsv::from_str("a,1\nb,2\na,3")?;
+        let summary = dsv.group_by(&[0])?.aggregate(&[Aggregate::sum(1)])?;
+        assert_eq!(summary.headers(), None);
+        assert_eq!(summary.records(), [["a", "4"], ["b", "2"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_group_values() -> Result<()> {
+        let dsv = Dsv::from_str("a,")?;
+        let summary = dsv
+            .group_by(&[0])?
+            .aggregate(&[Aggregate::mean(1), Aggregate::max(1)])?;
+        assert_eq!(summary[0], ["a", "", ""]);
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let options = Options {
+            null_values: vec!["NA".into()],
+            ..Default::default()
+        };
+        let dsv = Dsv::from_str_with_options("NA,1\n,2\nNA,3", options.clone())?;
+        let summary = dsv.group_by(&[0])?.aggregate(&[Aggregate::sum(1)])?;
+        assert_eq!(
+            summary,
+            Dsv::from_str_with_options("NA,4\n,2", options.clone())?
+        );
+
+        let dsv = Dsv::from_str_with_options("a,NA\na,\na,NA", options)?;
+        let summary = dsv
+            .group_by(&[0])?
+            .aggregate(&[Aggregate::distinct_count(1)])?;
+        assert_eq!(summary.records(), [["a", "2"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn not_a_number() {
+        let dsv = Dsv::from_str("a,1\na,x").unwrap();
+        let result = dsv.group_by(&[0]).unwrap().aggregate(&[Aggregate::sum(1)]);
+        assert!(matches!(
+            result,
+            Err(Error::Value { record: 1, column: 1, value }) if value == "x"
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+#[cfg(feature = "std")]
+use crate::mmap::Mmap;
+use crate::{
+    error::{Error, Result},
+    lexer::{Lexer, Token},
+    parser::skip_rows,
+    Options,
+};
+
+#[cfg(feature = "std")]
+const MAGIC: &[u8; 8] = b"DSVIDX1\0";
+
+/// An index of the byte offset at which each record starts.
+///
+/// Newlines within quoted values do not start a new record. An index can be
+/// saved alongside the file it was built from and used by a `Reader` to seek
+/// directly to any record.
+#[derive(PartialEq, Debug)]
+pub struct RecordIndex {
+    /// The length of the indexed text, used to detect stale indexes.
+    len: usize,
+    offsets: Vec<usize>,
+}
+
+impl RecordIndex {
+    /// Build an index over an input string.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Result<RecordIndex> {
+        RecordIndex::from_str_with_options(text, Options::default())
+    }
+
+    /// Build an index over an input string with options.
+    pub fn from_str_with_options(text: &str, options: Options) -> Result<RecordIndex> {
+        let mut lexer = Lexer::new(text, options.delimiter, options.quotes);
+        lexer.seek(skip_rows(text.as_bytes(), options.skip_rows));
+        let mut offsets = vec![];
+        let mut at_record_start = true;
+        loop {
+            let pos = lexer.pos();
+            let Some(token) = lexer.next()? else {
+                break;
+            };
+            if at_record_start {
+                offsets.push(pos);
+            }
+            at_record_start = token == Token::Newline;
+        }
+        Ok(RecordIndex {
+            len: text.len(),
+            offsets,
+        })
+    }
+
+    /// Build an index over a memory-mapped file.
+    #[cfg(feature = "std")]
+    pub fn from_mmap(mmap: &Mmap) -> Result<RecordIndex> {
+        RecordIndex::from_str(mmap.as_str())
+    }
+
+    /// Build an index over a memory-mapped file with options.
+    #[cfg(feature = "std")]
+    pub fn from_mmap_with_options(mmap: &Mmap, options: Options) -> Result<RecordIndex> {
+        RecordIndex::from_str_with_options(mmap.as_str(), options)
+    }
+
+    /// Get the number of indexed records.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Check whether the index contains no records.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Get the byte offset at which the specified record starts.
+    pub fn offset(&self, record: usize) -> Option<usize> {
+        self.offsets.get(record).copied()
+    }
+
+    /// Get the byte range spanned by the specified range of records.
+    ///
+    /// The range includes the newline that terminates the last record, if any.
+    pub fn byte_range(&self, records: Range<usize>) -> Option<Range<usize>> {
+        if records.start > records.end || records.end > self.offsets.len() {
+            return None;
+        }
+        let start = self.offsets.get(records.start).copied().unwrap_or(self.len);
+        let end = self.offsets.get(records.end).copied().unwrap_or(self.len);
+        Some(start..end)
+    }
+
+    /// Check that this index was built from the specified text.
+    pub(crate) fn check(&self, text: &str) -> Result<()> {
+        if self.len != text.len() {
+            return Err(Error::IndexMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl RecordIndex {
+    /// Write the index in a compact binary format.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(self.len as u64).to_le_bytes())?;
+        writer.write_all(&(self.offsets.len() as u64).to_le_bytes())?;
+        for offset in &self.offsets {
+            writer.write_all(&(*offset as u64).to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Read an index previously written with `write_to`.
+    ///
+    /// Fails with `Error::IndexMismatch` if the offsets are out of order or
+    /// past the end of the indexed text.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<RecordIndex> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            let error = io::Error::new(io::ErrorKind::InvalidData, "not a record index");
+            return Err(error.into());
+        }
+        let len = read_u64(&mut reader)? as usize;
+        let count = read_u64(&mut reader)? as usize;
+        let mut offsets = Vec::with_capacity(count.min(1 << 20));
+        for _ in 0..count {
+            let offset = read_u64(&mut reader)? as usize;
+            if offset > len || offsets.last().is_some_and(|&last| offset < last) {
+                return Err(Error::IndexMismatch);
+            }
+            offsets.push(offset);
+        }
+        Ok(RecordIndex { len, offsets })
+    }
+
+    /// Save the index to the file at the specified path.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load an index from the file at the specified path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<RecordIndex> {
+        RecordIndex::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(feature = "std")]
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets() -> Result<()> {
+        let text = "foo,bar\n\"baz\nqux\",bux\nx,y\n";
+        let index = RecordIndex::from_str(text)?;
+        assert_eq!(index.offsets, vec![0, 8, 22]);
+        assert_eq!(index.byte_range(1..3), Some(8..26));
+        assert_eq!(index.byte_range(3..3), Some(26..26));
+        assert_eq!(index.byte_range(2..4), None);
+        Ok(())
+    }
+
+    #[test]
+    fn empty() -> Result<()> {
+        let index = RecordIndex::from_str("")?;
+        assert!(index.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn round_trip() -> Result<()> {
+        let index = RecordIndex::from_str("foo\nbar\nbaz")?;
+        let mut bytes = vec![];
+        index.write_to(&mut bytes)?;
+        assert_eq!(RecordIndex::read_from(&bytes[..])?, index);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn corrupt_offsets() {
+        let read = |len: u64, offsets: &[u64]| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend(len.to_le_bytes());
+            bytes.extend((offsets.len() as u64).to_le_bytes());
+            for offset in offsets {
+                bytes.extend(offset.to_le_bytes());
+            }
+            RecordIndex::read_from(&bytes[..])
+        };
+        assert!(read(8, &[0, 4, 8]).is_ok());
+        assert!(matches!(read(8, &[0, 9]), Err(Error::IndexMismatch)));
+        assert!(matches!(read(8, &[0, 4, 2]), Err(Error::IndexMismatch)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn bad_magic() {
+        let bytes = b"notindex";
+        assert!(RecordIndex::read_from(&bytes[..]).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    convert::{Bool, NumberFormat},
+    datetime, Dsv,
+};
+
+/// The type of the values in a column.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataType {
+    Integer,
+    Float,
+    /// `true`/`false` or `yes`/`no`, ignoring case.
+    Boolean,
+    /// An ISO 8601 date, such as `2024-01-31`.
+    Date,
+    /// An ISO 8601 date and time, such as `2024-01-31T12:30:00Z`.
+    DateTime,
+    String,
+}
+
+impl DataType {
+    /// Check whether a value is of this type.
+    pub fn matches(&self, value: &str) -> bool {
+        self.matches_with(value, &NumberFormat::default())
+    }
+
+    /// Check whether a value is of this type, with numbers in a format.
+    pub fn matches_with(&self, value: &str, number_format: &NumberFormat) -> bool {
+        let value = value.trim();
+        match self {
+            DataType::Integer => number_format.parse_i64(value).is_ok(),
+            DataType::Float => number_format.parse_f64(value).is_ok(),
+            DataType::Boolean => {
+                !value.bytes().all(|b| b.is_ascii_digit()) && value.parse::<Bool>().is_ok()
+            }
+            DataType::Date => datetime::parse_iso_date(value).is_some(),
+            DataType::DateTime => datetime::is_iso_datetime(value),
+            DataType::String => true,
+        }
+    }
+}
+
+/// Candidate types, from most to least specific.
+const CANDIDATES: [DataType; 5] = [
+    DataType::Boolean,
+    DataType::Integer,
+    DataType::Float,
+    DataType::Date,
+    DataType::DateTime,
+];
+
+/// The inferred type of a column.
+#[derive(Clone, PartialEq, Debug)]
+pub struct InferredColumn {
+    /// The header of the column, or its index if there are no headers.
+    pub name: String,
+    pub data_type: DataType,
+    /// Whether any sampled value is null.
+    pub nullable: bool,
+    /// The fraction of sampled values, excluding nulls, that are of the
+    /// inferred type.
+    pub confidence: f64,
+}
+
+/// Options for schema inference.
+#[derive(Clone, Debug)]
+pub struct InferOptions {
+    /// Values that are treated as null. Default: empty, `NA`, `N/A`, `null`,
+    /// and `NULL`.
+    pub null_values: Vec<String>,
+    /// The number of records to sample from the start of the table, or `None`
+    /// to sample every record. Default: 1000.
+    pub sample_size: Option<usize>,
+    /// The smallest fraction of values that must be of a type for it to be
+    /// inferred, which allows for a few malformed values. Columns that do not
+    /// meet this for any other type are strings. Default: 1.0.
+    pub min_confidence: f64,
+    /// The format of numbers. Default: `NumberFormat::default()`.
+    pub number_format: NumberFormat,
+}
+
+impl Default for InferOptions {
+    fn default() -> Self {
+        Self {
+            null_values: ["", "NA", "N/A", "null", "NULL"].map(String::from).to_vec(),
+            sample_size: Some(1000),
+            min_confidence: 1.0,
+            number_format: NumberFormat::default(),
+        }
+    }
+}
+
+impl Dsv {
+    /// Infer the type of each column.
+    pub fn infer_schema(&self) -> Vec<InferredColumn> {
+        self.infer_schema_with_options(InferOptions::default())
+    }
+
+    /// Infer the type of each column with options.
+    ///
+    /// Each column is inferred to be the most specific type that meets the
+    /// minimum confidence, where integers are more specific than floats and
+    /// dates more specific than datetimes. Null fields and values that match
+    /// one of `InferOptions::null_values` are ignored, and a column of only
+    /// nulls is a string with a confidence of zero.
+    pub fn infer_schema_with_options(&self, options: InferOptions) -> Vec<InferredColumn> {
+        let sample_size = options.sample_size.unwrap_or(usize::MAX);
+        let sample = &self.records[..self.records.len().min(sample_size)];
+
+        (0..self.width())
+            .map(|column| {
+                let mut nullable = false;
+                let mut total = 0;
+                let mut counts = vec![0; CANDIDATES.len()];
+                for (ix, record) in sample.iter().enumerate() {
+                    let value = &record[column];
+                    if self.is_null(ix, column) || options.null_values.contains(value) {
+                        nullable = true;
+                        continue;
+                    }
+                    total += 1;
+                    for (count, candidate) in counts.iter_mut().zip(CANDIDATES) {
+                        *count += candidate.matches_with(value, &options.number_format) as usize;
+                    }
+                }
+
+                let inferred = CANDIDATES
+                    .iter()
+                    .zip(counts)
+                    .map(|(&data_type, count)| (data_type, count as f64 / total as f64))
+                    .find(|&(_, confidence)| total > 0 && confidence >= options.min_confidence);
+                let (data_type, confidence) = match inferred {
+                    Some(inferred) => inferred,
+                    None if total == 0 => (DataType::String, 0.0),
+                    None => (DataType::String, 1.0),
+                };
+
+                InferredColumn {
+                    name: match self.headers() {
+                        Some(headers) => headers[column].clone(),
+                        None => column.to_string(),
+                    },
+                    data_type,
+                    nullable,
+                    confidence,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DsvBuilder;
+
+    fn sample() -> Dsv {
+        DsvBuilder::new()
+            .headers(["id", "price", "active", "day", "seen", "name", "empty"])
+            .record(["1", "1.5", "yes", "2024-01-31", "2024-01-31T10:00", "a", ""])
+            .record(["2", "2", "No", "2024-02-01", "2024-02-01", "b", "NA"])
+            .record(["3", "", "true", "", "2024-02-01 10:00:00Z", "3", ""])
+            .record(["x", "4e2", "false", "2024-02-03", "", "d", ""])
+            .build()
+            .unwrap()
+    }
+
+    fn types(schema: &[InferredColumn]) -> Vec<DataType> {
+        schema.iter().map(|column| column.data_type).collect()
+    }
+
+    #[test]
+    fn infer() {
+        let schema = sample().infer_schema();
+        assert_eq!(
+            types(&schema),
+            [
+                DataType::String,
+                DataType::Float,
+                DataType::Boolean,
+                DataType::Date,
+                DataType::DateTime,
+                DataType::String,
+                DataType::String,
+            ]
+        );
+        assert_eq!(schema[1].name, "price");
+        assert!(schema[1].nullable);
+        assert!(!schema[2].nullable);
+        assert_eq!(schema[6].confidence, 0.0);
+    }
+
+    #[test]
+    fn confidence() {
+        let options = InferOptions {
+            min_confidence: 0.7,
+            ..Default::default()
+        };
+        let schema = sample().infer_schema_with_options(options);
+        assert_eq!(schema[0].data_type, DataType::Integer);
+        assert_eq!(schema[0].confidence, 0.75);
+    }
+
+    #[test]
+    fn sample_size() {
+        let options = InferOptions {
+            sample_size: Some(3),
+            ..Default::default()
+        };
+        let schema = sample().infer_schema_with_options(options);
+        assert_eq!(schema[0].data_type, DataType::Integer);
+        assert_eq!(schema[0].confidence, 1.0);
+    }
+
+    #[test]
+    fn number_format() {
+        let options = crate::Options {
+            delimiter: b';',
+            ..Default::default()
+        };
+        let dsv = Dsv::from_str_with_options("1.234,5;1.000\n-2,25;12", options).unwrap();
+        let schema = dsv.infer_schema();
+        assert_eq!(types(&schema), [DataType::String, DataType::Float]);
+
+        let options = InferOptions {
+            number_format: NumberFormat::new().decimal(',').grouping('.'),
+            ..Default::default()
+        };
+        let schema = dsv.infer_schema_with_options(options);
+        assert_eq!(types(&schema), [DataType::Float, DataType::Integer]);
+    }
+
+    #[test]
+    fn null_values() {
+        let dsv = Dsv::from_str("1\n-\n2").unwrap();
+        assert_eq!(dsv.infer_schema()[0].data_type, DataType::String);
+        let options = InferOptions {
+            null_values: vec!["-".into()],
+            ..Default::default()
+        };
+        let schema = dsv.infer_schema_with_options(options);
+        assert_eq!(schema[0].name, "0");
+        assert_eq!(schema[0].data_type, DataType::Integer);
+        assert!(schema[0].nullable);
+    }
+
+    #[test]
+    fn parsed_nulls() {
+        let options = crate::Options {
+            null_values: vec!["NA".into()],
+            ..Default::default()
+        };
+        let dsv = Dsv::from_str_with_options("1\nNA\n2", options).unwrap();
+        let options = InferOptions {
+            null_values: vec![],
+            ..Default::default()
+        };
+        let schema = dsv.infer_schema_with_options(options);
+        assert_eq!(schema[0].data_type, DataType::Integer);
+        assert!(schema[0].nullable);
+    }
+}
@@ -0,0 +1,266 @@
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+
+use crate::{
+    column::Column,
+    error::{Error, Result},
+    Dsv,
+};
+
+/// Which records to keep when joining two tables.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum JoinKind {
+    /// Keep only records whose key is in both tables.
+    #[default]
+    Inner,
+    /// Keep every record of the left table.
+    Left,
+    /// Keep every record of the right table.
+    Right,
+    /// Keep every record of both tables.
+    Full,
+}
+
+impl Dsv {
+    /// Join with another table on one or more key columns.
+    ///
+    /// Conflicting header names are suffixed with `_left` and `_right`. See
+    /// `Dsv::join_with_suffixes` for details.
+    pub fn join<'c, C: Copy + Into<Column<'c>>>(
+        &self,
+        other: &Dsv,
+        on: &[C],
+        kind: JoinKind,
+    ) -> Result<Dsv> {
+        self.join_with_suffixes(other, on, kind, ("_left", "_right"))
+    }
+
+    /// Join with another table on one or more key columns, with suffixes for
+    /// conflicting header names.
+    ///
+    /// Key columns are resolved in both tables, so names must appear in both
+    /// sets of headers. Each record of the result contains the key columns,
+    /// followed by the other columns of this table and then those of the other
+    /// table. A key that appears more than once produces a record for every
+    /// matching pair, and a null key only matches a null key. Records without
+    /// a match are filled in with empty fields, while null fields stay null.
+    /// The result follows the order of this table, with unmatched records of
+    /// the other table at the end. Non-key headers that appear in both tables
+    /// are renamed using `suffixes`.
+    pub fn join_with_suffixes<'c, C: Copy + Into<Column<'c>>>(
+        &self,
+        other: &Dsv,
+        on: &[C],
+        kind: JoinKind,
+        suffixes: (&str, &str),
+    ) -> Result<Dsv> {
+        if self.headers.is_some() != other.headers.is_some() {
+            return Err(Error::Headers);
+        }
+        let left_keys = self.resolve_columns(on)?;
+        let right_keys = other.resolve_columns(on)?;
+        let left_rest = rest(self.width(), &left_keys);
+        let right_rest = rest(other.width(), &right_keys);
+
+        let headers = match (self.headers(), other.headers()) {
+            (Some(left), Some(right)) => {
+                let suffixed = |name: &String, others: &[String], others_rest: &[usize], suffix| {
+                    if others_rest.iter().any(|&c| &others[c] == name) {
+                        format!("{name}{suffix}")
+                    } else {
+                        name.clone()
+                    }
+                };
+                let keys = left_keys.iter().map(|&c| left[c].clone());
+                let left_fields = left_rest
+                    .iter()
+                    .map(|&c| suffixed(&left[c], right, &right_rest, suffixes.0));
+                let right_fields = right_rest
+                    .iter()
+                    .map(|&c| suffixed(&right[c], left, &left_rest, suffixes.1));
+                Some(keys.chain(left_fields).chain(right_fields).collect())
+            }
+            _ => None,
+        };
+
+        let mut lookup: BTreeMap<Vec<Option<&str>>, Vec<usize>> = BTreeMap::new();
+        for ix in 0..other.records.len() {
+            lookup
+                .entry(key(other, ix, &right_keys))
+                .or_default()
+                .push(ix);
+        }
+
+        let fields = |dsv: &Dsv, ix: usize, columns: &[usize]| -> Vec<Option<String>> {
+            columns
+                .iter()
+                .map(|&c| dsv.field_opt(ix, c).map(String::from))
+                .collect()
+        };
+        let empty = |len: usize| vec![Some(String::new()); len];
+        let mut records = vec![];
+        let mut matched = vec![false; other.records.len()];
+        for left in 0..self.records.len() {
+            let build = |right: Option<usize>| {
+                let mut record = fields(self, left, &left_keys);
+                record.extend(fields(self, left, &left_rest));
+                match right {
+                    Some(right) => record.extend(fields(other, right, &right_rest)),
+                    None => record.extend(empty(right_rest.len())),
+                }
+                record
+            };
+            match lookup.get(&key(self, left, &left_keys)) {
+                Some(matches) => {
+                    for &ix in matches {
+                        matched[ix] = true;
+                        records.push(build(Some(ix)));
+                    }
+                }
+                None if matches!(kind, JoinKind::Left | JoinKind::Full) => {
+                    records.push(build(None));
+                }
+                None => {}
+            }
+        }
+
+        if matches!(kind, JoinKind::Right | JoinKind::Full) {
+            let unmatched = (0..other.records.len()).filter(|&ix| !matched[ix]);
+            for right in unmatched {
+                let mut record = fields(other, right, &right_keys);
+                record.extend(empty(left_rest.len()));
+                record.extend(fields(other, right, &right_rest));
+                records.push(record);
+            }
+        }
+
+        Ok(Dsv::from_opt(headers, records))
+    }
+}
+
+/// Get the columns of a table that are not keys.
+fn rest(width: usize, keys: &[usize]) -> Vec<usize> {
+    (0..width).filter(|c| !keys.contains(c)).collect()
+}
+
+/// Get the key of a record, with `None` for null fields.
+fn key<'a>(dsv: &'a Dsv, record: usize, keys: &[usize]) -> Vec<Option<&'a str>> {
+    keys.iter().map(|&c| dsv.field_opt(record, c)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsvBuilder, Options};
+
+    fn orders() -> Dsv {
+        DsvBuilder::new()
+            .headers(["id", "customer", "total"])
+            .record(["1", "ann", "10"])
+            .record(["2", "bob", "20"])
+            .record(["3", "cat", "30"])
+            .record(["4", "ann", "40"])
+            .build()
+            .unwrap()
+    }
+
+    fn customers() -> Dsv {
+        DsvBuilder::new()
+            .headers(["customer", "id", "city"])
+            .record(["ann", "a", "Berlin"])
+            .record(["bob", "b", "Paris"])
+            .record(["dan", "d", "Rome"])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn inner() -> Result<()> {
+        let joined = orders().join(&customers(), &["customer"], JoinKind::Inner)?;
+        assert_eq!(
+            joined.headers().unwrap(),
+            ["customer", "id_left", "total", "id_right", "city"]
+        );
+        assert_eq!(
+            joined.records(),
+            [
+                ["ann", "1", "10", "a", "Berlin"],
+                ["bob", "2", "20", "b", "Paris"],
+                ["ann", "4", "40", "a", "Berlin"],
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn left_right_full() -> Result<()> {
+        let left = orders().join(&customers(), &["customer"], JoinKind::Left)?;
+        assert_eq!(left.records().len(), 4);
+        assert_eq!(left[2], ["cat", "3", "30", "", ""]);
+
+        let right = orders().join(&customers(), &["customer"], JoinKind::Right)?;
+        assert_eq!(right.records().len(), 4);
+        assert_eq!(right[3], ["dan", "", "", "d", "Rome"]);
+
+        let full = orders().join(&customers(), &["customer"], JoinKind::Full)?;
+        assert_eq!(full.records().len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys() -> Result<()> {
+        let left = Dsv::from_str("a,1\na,2")?;
+        let right = Dsv::from_str("a,x\na,y")?;
+        let joined = left.join(&right, &[0], JoinKind::Inner)?;
+        assert_eq!(
+            joined.records(),
+            [
+                ["a", "1", "x"],
+                ["a", "1", "y"],
+                ["a", "2", "x"],
+                ["a", "2", "y"]
+            ]
+        );
+        assert_eq!(joined.headers(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn suffixes() -> Result<()> {
+        let joined = orders().join_with_suffixes(
+            &customers(),
+            &["customer"],
+            JoinKind::Inner,
+            ("", "_c"),
+        )?;
+        assert_eq!(
+            joined.headers().unwrap(),
+            ["customer", "id", "total", "id_c", "city"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let read = |text| {
+            let options = Options {
+                headers: true,
+                null_values: vec!["NA".into()],
+                ..Default::default()
+            };
+            Dsv::from_str_with_options(text, options)
+        };
+        let left = read("k,a\n1,NA\nNA,x\n,y\n2,z")?;
+        let right = read("k,b\nNA,p\n,q\n1,NA")?;
+        let joined = left.join(&right, &["k"], JoinKind::Left)?;
+        assert_eq!(joined, read("k,a,b\n1,NA,NA\nNA,x,p\n,y,q\n2,z,")?);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let result = orders().join(&customers(), &["total"], JoinKind::Inner);
+        assert!(matches!(result, Err(Error::UnknownColumn(name)) if name == "total"));
+        let result = orders().join(&Dsv::from_str("a").unwrap(), &[0], JoinKind::Inner);
+        assert!(matches!(result, Err(Error::Headers)));
+    }
+}
@@ -1,9 +1,15 @@
-use crate::{error::Result, parser::Quotes};
+use alloc::borrow::Cow;
+
+use crate::{
+    error::{Error, Result},
+    parser::Quotes,
+};
 
 #[derive(Debug, PartialEq)]
-pub enum Token {
+pub enum Token<'a> {
     Delimiter,
-    Value(String),
+    /// A value, borrowed from the input unless unescaping was required.
+    Value(Cow<'a, str>),
     Newline,
 }
 
@@ -24,39 +30,62 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    pub fn next(&mut self) -> Result<Option<Token>> {
+    pub fn next(&mut self) -> Result<Option<Token<'a>>> {
         use Token::*;
 
         if self.pos == self.text.len() {
             return Ok(None);
         }
 
-        if let Some(_) = self.scan_delim() {
+        if self.scan_delim().is_some() {
             self.pos += 1;
             return Ok(Some(Delimiter));
         }
 
-        if let Some(_) = self.scan_newline() {
-            self.pos += 1;
+        if let Some(len) = self.scan_newline() {
+            self.pos += len;
             return Ok(Some(Newline));
         }
 
         if matches!(self.quotes, Quotes::Significant) {
-            if let Some(len) = self.scan_quoted_value() {
-                let value = &self.text[self.pos + 1..self.pos + 1 + len];
-                let value = value.replace(r#""""#, r#"""#);
+            if let Some(len) = self.scan_quoted_value()? {
+                let text = self.text;
+                let value = &text[self.pos + 1..self.pos + 1 + len];
+                let value = if value.contains(r#""""#) {
+                    Cow::Owned(value.replace(r#""""#, r#"""#))
+                } else {
+                    Cow::Borrowed(value)
+                };
                 self.pos += len + 2;
-                return Ok(Some(Value(value.into())));
+                return Ok(Some(Value(value)));
             }
         }
 
         let len = self.scan_value();
-        let value = &self.text[self.pos..self.pos + len];
+        let text = self.text;
+        let value = &text[self.pos..self.pos + len];
         self.pos += len;
-        Ok(Some(Value(value.into())))
+        Ok(Some(Value(Cow::Borrowed(value))))
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn seek(&mut self, pos: usize) {
+        assert!(pos <= self.text.len());
+        self.pos = pos;
     }
 
-    pub fn peek(&mut self) -> Result<Option<Token>> {
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn quotes(&self) -> Quotes {
+        self.quotes
+    }
+
+    pub fn peek(&mut self) -> Result<Option<Token<'a>>> {
         let start = self.pos;
         let token = self.next();
         self.pos = start;
@@ -73,22 +102,17 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    fn scan_newline(&self) -> Option<()> {
+    fn scan_newline(&self) -> Option<usize> {
         assert!(self.pos < self.text.len());
-        let current = self.text.as_bytes()[self.pos];
-        if current == b'\n' {
-            Some(())
-        } else {
-            None
-        }
+        newline_len(&self.text.as_bytes()[self.pos..])
     }
 
-    fn scan_quoted_value(&self) -> Option<usize> {
+    fn scan_quoted_value(&self) -> Result<Option<usize>> {
         assert!(self.pos < self.text.len());
         let bytes = self.text.as_bytes();
         let current = bytes[self.pos];
         if current != b'"' {
-            return None;
+            return Ok(None);
         }
         let mut ix = self.pos + 1;
         let mut len = 0;
@@ -105,7 +129,11 @@ impl<'a> Lexer<'a> {
             ix += 1;
             len += 1;
         }
-        Some(len)
+        // disallow unterminated quotes
+        if ix == bytes.len() {
+            return Err(Error::Parse);
+        }
+        Ok(Some(len))
     }
 
     fn scan_value(&self) -> usize {
@@ -113,7 +141,10 @@ impl<'a> Lexer<'a> {
         let bytes = self.text.as_bytes();
         let mut ix = self.pos;
         let mut len = 0;
-        while ix < self.text.len() && bytes[ix] != self.delimiter && bytes[ix] != b'\n' {
+        while ix < self.text.len()
+            && bytes[ix] != self.delimiter
+            && newline_len(&bytes[ix..]).is_none()
+        {
             ix += 1;
             len += 1;
         }
@@ -121,6 +152,17 @@ impl<'a> Lexer<'a> {
     }
 }
 
+/// Returns the length of the newline at the start of the input, if any.
+///
+/// Both `\n` and `\r\n` are recognized as newlines.
+fn newline_len(bytes: &[u8]) -> Option<usize> {
+    match bytes {
+        [b'\n', ..] => Some(1),
+        [b'\r', b'\n', ..] => Some(2),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Token::*, *};
@@ -157,6 +199,16 @@ mod tests {
         assert_eq!(token, Some(Newline));
     }
 
+    #[test]
+    fn crlf_newline() -> Result<()> {
+        let text = "foo\r\nbar\rbaz";
+        let mut lexer = Lexer::new(text, b',', Quotes::Significant);
+        assert_eq!(lexer.next()?, Some(Value("foo".into())));
+        assert_eq!(lexer.next()?, Some(Newline));
+        assert_eq!(lexer.next()?, Some(Value("bar\rbaz".into())));
+        Ok(())
+    }
+
     #[test]
     fn empty() {
         let text = "";
@@ -232,6 +284,13 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn unterminated_quote() {
+        let text = r#""foo"#;
+        let token = Lexer::new(text, b',', Quotes::Significant).next();
+        assert!(token.is_err());
+    }
+
     #[test]
     fn escaped_quote() {
         let text = r#""foo""""#;
@@ -49,6 +49,331 @@
 //! assert_eq!(dsv[1][1], "moon");
 //! ```
 //!
+//! To work with a table column by column, use `Dsv::column` or `Dsv::columns`.
+//! To create a table from a subset of columns, use `Dsv::select`.
+//!
+//! ```
+//! use dsv::Dsv;
+//!
+//! let text = ["apples,2,red", "bananas,4,yellow"].join("\n");
+//! let dsv = Dsv::from_str(&text).unwrap();
+//!
+//! let counts: Vec<_> = dsv.column(1).unwrap().collect();
+//! assert_eq!(counts, ["2", "4"]);
+//!
+//! let colors = dsv.select(&[2, 0]).unwrap();
+//! assert_eq!(colors[1], ["yellow", "bananas"]);
+//! ```
+//!
+//! Fields are stored as text. To parse them into other types, use
+//! `Record::get_as` or `Dsv::parse_column`, which work with any type that
+//! implements `FromStr`. `Bool`, `Thousands`, `Percent`, and `DateTime` parse
+//! common formats that the standard library does not. Dates and times can also
+//! be parsed with an explicit format using `Record::get_datetime` or
+//! `Dsv::parse_datetime_column`, and numbers with locale-specific separators
+//! and symbols using a `NumberFormat` with `Record::get_number` or
+//! `Dsv::parse_number_column`.
+//!
+//! ```
+//! use dsv::{Dsv, Percent};
+//!
+//! let dsv = Dsv::from_str("apples,2,25%\nbananas,4,12.5%").unwrap();
+//! let counts: Vec<u32> = dsv.parse_column(1).unwrap();
+//! assert_eq!(counts, [2, 4]);
+//!
+//! let share = dsv.record(1).unwrap().get_as::<Percent>(2).unwrap();
+//! assert_eq!(share, Percent(0.125));
+//! ```
+//!
+//! To find out the type of each column, use `Dsv::infer_schema`.
+//!
+//! ```
+//! use dsv::{DataType, Dsv};
+//!
+//! let dsv = Dsv::from_str("apples,2,2024-01-31\nbananas,,2024-02-01").unwrap();
+//! let schema = dsv.infer_schema();
+//!
+//! assert_eq!(schema[1].data_type, DataType::Integer);
+//! assert!(schema[1].nullable);
+//! assert_eq!(schema[2].data_type, DataType::Date);
+//! ```
+//!
+//! To check a table against the columns you expect, declare a `Schema` and
+//! use `Dsv::validate`, or a `Validator` to check records as they are read.
+//!
+//! ```
+//! use dsv::{DataType, Dsv, Field, Schema, ViolationKind};
+//!
+//! let schema = Schema::new(vec![
+//!     Field::new("fruit", DataType::String).required(),
+//!     Field::new("count", DataType::Integer).minimum(0.0),
+//! ])
+//! .unique(["fruit"]);
+//! let dsv = Dsv::from_str("apples,2\noranges,-3\napples,4").unwrap();
+//! let violations = dsv.validate(&schema).unwrap();
+//!
+//! assert_eq!(violations[0].kind, ViolationKind::BelowMinimum);
+//! assert_eq!(violations[1].kind, ViolationKind::Duplicate { first: 0 });
+//! ```
+//!
+//! Schemas and parser options can also be loaded from Frictionless Table
+//! Schema or CSVW metadata with `Metadata`.
+//!
+//! To summarize the values of each column, with counts, ranges, quantiles, and
+//! the most frequent values, use `Dsv::describe`, or a `Describer` to
+//! summarize records as they are read.
+//!
+//! To compare two versions of a table, use `diff` to match records by key
+//! columns, or `diff_by_order` for tables without a key. The resulting `Diff`
+//! lists the added, removed, and changed records, and displays as a unified
+//! report. A `Patch` made from a diff can be stored as a table and applied to
+//! another copy of the old table with `Dsv::apply_patch`, which detects
+//! records that no longer match.
+//!
+//! To construct a table programmatically, use a `DsvBuilder` or
+//! `Dsv::from_records`. Fields can be anything that implements `ToString`.
+//!
+//! ```
+//! use dsv::{Dsv, DsvBuilder};
+//!
+//! let dsv = DsvBuilder::new()
+//!     .headers(["fruit", "count"])
+//!     .record(["apples", "2"])
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(dsv[0][0], "apples");
+//!
+//! let dsv = Dsv::from_records([[1, 2], [3, 4]]).unwrap();
+//! assert_eq!(dsv[1][0], "3");
+//! ```
+//!
+//! To modify a table, use methods such as `Dsv::push_record` and
+//! `Dsv::insert_column`. These keep every record, and the headers, the same
+//! length, and return an error rather than panicking when they can not.
+//!
+//! ```
+//! use dsv::{Dsv, Options};
+//!
+//! let options = Options { headers: true, ..Default::default() };
+//! let mut dsv = Dsv::from_str_with_options("fruit\napples", options).unwrap();
+//! dsv.insert_column(1, Some("count".into()), vec!["2".into()]).unwrap();
+//! dsv.push_record(vec!["oranges".into(), "3".into()]).unwrap();
+//!
+//! assert_eq!(dsv.headers().unwrap(), ["fruit", "count"]);
+//! assert!(dsv.push_record(vec!["bananas".into()]).is_err());
+//! ```
+//!
+//! To sort a table, use `Dsv::sort_by_columns` with one or more keys. Columns
+//! can be referred to by index or, if the table has headers, by name. Each key
+//! can be sorted in either direction and compared lexically, numerically,
+//! naturally, or ignoring case. To sort a stream that is larger than memory,
+//! use `external_sort`.
+//!
+//! ```
+//! use dsv::{Compare, Dsv, SortKey};
+//!
+//! let text = ["file10,3", "file2,1", "file2,4"].join("\n");
+//! let mut dsv = Dsv::from_str(&text).unwrap();
+//! let keys = [
+//!     SortKey::new(0).by(Compare::Natural),
+//!     SortKey::new(1).by(Compare::Numeric).descending(),
+//! ];
+//! dsv.sort_by_columns(&keys).unwrap();
+//!
+//! assert_eq!(dsv[0], ["file2", "4"]);
+//! assert_eq!(dsv[2], ["file10", "3"]);
+//! ```
+//!
+//! To select records, use `Dsv::filter` with an expression that compares
+//! columns with values. See `Filter` for the full syntax.
+//!
+//! ```
+//! use dsv::{Dsv, Options};
+//!
+//! let text = ["fruit,price,country", "apples,12,DE", "oranges,8,DE"].join("\n");
+//! let options = Options { headers: true, ..Default::default() };
+//! let dsv = Dsv::from_str_with_options(&text, options).unwrap();
+//! let expensive = dsv.filter("price > 10 && country == 'DE'").unwrap();
+//!
+//! assert_eq!(expensive.records(), [["apples", "12", "DE"]]);
+//! ```
+//!
+//! To summarize a table, group its records with `Dsv::group_by` and compute
+//! an `Aggregate` over each group. Numbers are parsed from fields as needed.
+//!
+//! ```
+//! use dsv::{Aggregate, Dsv};
+//!
+//! let dsv = Dsv::from_str("DE,12\nFR,3\nDE,8").unwrap();
+//! let summary = dsv
+//!     .group_by(&[0])
+//!     .unwrap()
+//!     .aggregate(&[Aggregate::count(), Aggregate::mean(1)])
+//!     .unwrap();
+//!
+//! assert_eq!(summary.records(), [["DE", "2", "10"], ["FR", "1", "3"]]);
+//! ```
+//!
+//! To combine two tables, use `Dsv::join` with one or more key columns.
+//!
+//! ```
+//! use dsv::{Dsv, JoinKind, Options};
+//!
+//! let options = Options { headers: true, ..Default::default() };
+//! let orders = Dsv::from_str_with_options("id,fruit\n1,apples", options.clone()).unwrap();
+//! let prices = Dsv::from_str_with_options("fruit,price\napples,2", options).unwrap();
+//! let joined = orders.join(&prices, &["fruit"], JoinKind::Inner).unwrap();
+//!
+//! assert_eq!(joined.headers().unwrap(), ["fruit", "id", "price"]);
+//! assert_eq!(joined[0], ["apples", "1", "2"]);
+//! ```
+//!
+//! To remove duplicate records, use `Dsv::dedup` or `Dsv::dedup_by`, keeping
+//! either the first or last occurrence of each. `Dsv::distinct` and
+//! `Dsv::value_counts` summarize the values of a column. To skip duplicates in
+//! a stream, wrap the records in a `Dedup`.
+//!
+//! ```
+//! use dsv::{Dsv, Keep};
+//!
+//! let mut dsv = Dsv::from_str("apples,2\noranges,3\napples,4").unwrap();
+//! let counts = dsv.value_counts(0).unwrap();
+//! assert_eq!(counts, [(Some("apples"), 2), (Some("oranges"), 1)]);
+//!
+//! dsv.dedup_by(&[0], Keep::Last).unwrap();
+//! assert_eq!(dsv.records(), [["oranges", "3"], ["apples", "4"]]);
+//! ```
+//!
+//! Tables can be reshaped with `Dsv::transpose`, `Dsv::pivot`, which turns
+//! the values of a column into columns of their own, and `Dsv::melt`, which
+//! does the reverse.
+//!
+//! ```
+//! use dsv::Dsv;
+//!
+//! let dsv = Dsv::from_str("apples,mon,2\napples,tue,3\noranges,mon,4").unwrap();
+//! let wide = dsv.pivot(0, 1, 2).unwrap();
+//!
+//! assert_eq!(wide.headers().unwrap(), ["0", "mon", "tue"]);
+//! assert_eq!(wide.records(), [["apples", "2", "3"], ["oranges", "4", ""]]);
+//! ```
+//!
+//! To combine tables, use `Dsv::concat`, which matches columns by header name.
+//! To add tables to an existing file, open a `Writer` in append mode with
+//! `Writer::append_path`, which writes headers only if the file has none.
+//!
+//! ```
+//! use dsv::{Dsv, Options};
+//!
+//! let options = Options { headers: true, ..Default::default() };
+//! let january = Dsv::from_str_with_options("fruit,count\napples,2", options.clone()).unwrap();
+//! let february = Dsv::from_str_with_options("count,fruit\n3,oranges", options).unwrap();
+//! let dsv = Dsv::concat([&january, &february]).unwrap();
+//!
+//! assert_eq!(dsv.records(), [["apples", "2"], ["oranges", "3"]]);
+//! ```
+//!
+//! To read a file, use `Dsv::from_path`.
+//!
+//! ```no_run
+//! # #[cfg(feature = "std")] {
+//! use dsv::Dsv;
+//!
+//! let dsv = Dsv::from_path("groceries.csv").unwrap();
+//! # }
+//! ```
+//!
+//! To parse records lazily instead of building a table, use a `Reader`. Fields
+//! borrow from the input where possible. Opening an `Mmap` is `unsafe`, because
+//! the file must not change while it is mapped.
+//!
+//! ```no_run
+//! # #[cfg(feature = "std")] {
+//! use dsv::{Mmap, Reader};
+//!
+//! // SAFETY: nothing else modifies the file while it is mapped.
+//! let mmap = unsafe { Mmap::open("groceries.csv").unwrap() };
+//! for record in Reader::from_mmap(&mmap) {
+//!     let record = record.unwrap();
+//!     println!("Fruit {}, count {}", record[0], record[1]);
+//! }
+//! # }
+//! ```
+//!
+//! To jump straight to a record in a large file, build a `RecordIndex`. The
+//! index can be saved alongside the file and loaded again later.
+//!
+//! ```
+//! use dsv::{Reader, RecordIndex};
+//!
+//! let text = ["apples,2", "oranges,3", "bananas,4"].join("\n");
+//! let index = RecordIndex::from_str(&text).unwrap();
+//! let mut reader = Reader::from_str(&text);
+//! reader.seek(&index, 2).unwrap();
+//!
+//! assert_eq!(reader.next().unwrap().unwrap(), vec!["bananas", "4"]);
+//! ```
+//!
+//! To parse records from a source implementing `Read` without holding the
+//! whole input in memory, use a `StreamReader`. Records can be written back
+//! out with a `Writer`, which quotes fields only when necessary.
+//!
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use dsv::{StreamReader, Writer};
+//!
+//! let input = "apples,2\n\"oranges, blood\",3\n";
+//! let mut writer = Writer::new(vec![]);
+//! for record in StreamReader::new(input.as_bytes()) {
+//!     writer.write_record(record.unwrap()).unwrap();
+//! }
+//!
+//! assert_eq!(writer.into_inner(), input.as_bytes());
+//! # }
+//! ```
+//!
+//! With the `async` feature enabled, `AsyncReader` and `AsyncWriter` provide
+//! the same functionality over tokio's `AsyncRead` and `AsyncWrite`, with
+//! records yielded as a `Stream`.
+//!
+//! For lower-level control, such as in network protocols or decompression
+//! pipelines, a `Decoder` can be fed chunks of bytes of any size and yields
+//! records as they are completed.
+//!
+//! ```
+//! use dsv::{Decoded, Decoder};
+//!
+//! let mut decoder = Decoder::new();
+//! assert_eq!(decoder.decode(b"\"hello,").unwrap(), (7, Decoded::NeedMore));
+//! assert_eq!(
+//!     decoder.decode(b" sun\",moon\r\n").unwrap(),
+//!     (12, Decoded::Record(vec!["hello, sun".into(), "moon".into()])),
+//! );
+//! ```
+//!
+//! # Features
+//!
+//! * `std` (default): Enables reading from files and streams and writing
+//!   output. Without it, the crate is `no_std` and only requires `alloc`.
+//!   `Dsv`, `Reader`, `RecordIndex`, and `Decoder` remain available, as does
+//!   `RawReader`, which writes the span of each field into a caller-provided
+//!   buffer and never allocates.
+//! * `async`: Enables `AsyncReader` and `AsyncWriter`.
+//! * `regex` (default): Enables regular expression matching in filters.
+//! * `chrono`: Enables converting a `DateTime` into the types of the `chrono`
+//!   crate.
+//!
+//! ```
+//! use dsv::{RawReader, Span};
+//!
+//! let input = b"hello,\"sun\"\nhello,moon";
+//! let mut reader = RawReader::new(input);
+//! let mut fields = [Span::default(); 2];
+//! while let Some(len) = reader.read_record(&mut fields).unwrap() {
+//!     assert_eq!(fields[0].bytes(input), b"hello");
+//! }
+//! ```
+//!
 //! # Options
 //!
 //! The following parser options are available.
@@ -59,16 +384,99 @@
 //!   set to `Quotes::Insignificant`, double quotes are treated like any other
 //!   character, and characters that would otherwise be valid within quotes
 //!   (such as the delimiter) can not be used in values.
+//! * `headers`: Whether the first record contains the headers of the table.
+//!   Default: `false`. If set, the headers are available from `Dsv::headers`
+//!   rather than as a record. Readers always return the headers as a record.
+//! * `projection`: The columns to keep from each record, in order. Default:
+//!   `None`. Unselected fields are skipped rather than copied out of the input.
+//! * `skip_rows`: The number of lines to skip before the table. Default: `0`.
+//! * `null_values`: Values that represent a missing value, such as `NA`.
+//!   Default: empty. Unquoted fields that match one are null, which
+//!   `Record::get_opt` reports as `None` and writers write back unchanged.
+//!   Readers return nulls as `None` from `next_record_opt` or
+//!   `read_record_opt`.
 //!
 //! # Additional considerations
 //!
+//! Records are separated by either `\n` or `\r\n`. A lone `\r` is treated as
+//! part of a value.
+//!
 //! Each record in a DSV is expected to contain the same number of fields. The
 //! expected length is based on the length of the first record in the table.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "async")]
+mod async_io;
+mod builder;
+mod column;
+mod concat;
+mod convert;
+mod datetime;
+mod decoder;
+mod dedup;
+mod describe;
+mod diff;
 mod dsv;
 mod error;
+mod filter;
+mod group;
+mod index;
+mod infer;
+mod join;
+mod json;
 mod lexer;
+mod metadata;
+#[cfg(feature = "std")]
+mod mmap;
 mod parser;
+mod patch;
+mod raw;
+mod reader;
+mod record;
+mod reshape;
+mod schema;
+mod sort;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod writer;
 
+#[cfg(feature = "async")]
+pub use crate::async_io::{AsyncReader, AsyncWriter};
+pub use crate::builder::DsvBuilder;
+pub use crate::column::{Column, Columns, Fields};
+pub use crate::concat::Align;
+pub use crate::convert::{Bool, NumberFormat, Percent, Thousands};
+pub use crate::datetime::DateTime;
+pub use crate::decoder::{Decoded, Decoder};
+#[cfg(feature = "std")]
+pub use crate::dedup::Dedup;
+pub use crate::dedup::Keep;
+pub use crate::describe::{ColumnSummary, DescribeOptions, Describer, NumericSummary};
+pub use crate::diff::{diff, diff_by_order, Diff, DiffKind, FieldDiff, RecordDiff};
 pub use crate::dsv::Dsv;
-pub use crate::parser::Options;
+pub use crate::error::{Error, Result};
+pub use crate::filter::Filter;
+pub use crate::group::{Aggregate, GroupBy};
+pub use crate::index::RecordIndex;
+pub use crate::infer::{DataType, InferOptions, InferredColumn};
+pub use crate::join::JoinKind;
+pub use crate::metadata::Metadata;
+#[cfg(feature = "std")]
+pub use crate::mmap::Mmap;
+pub use crate::parser::{Options, Quotes};
+pub use crate::patch::{Patch, PatchOperation};
+pub use crate::raw::{RawReader, Span};
+pub use crate::reader::Reader;
+pub use crate::record::{Record, Records};
+pub use crate::schema::{Field, Schema, Validator, Violation, ViolationKind};
+#[cfg(feature = "std")]
+pub use crate::sort::external_sort;
+pub use crate::sort::{Compare, Order, SortKey};
+#[cfg(feature = "std")]
+pub use crate::stream::StreamReader;
+#[cfg(feature = "std")]
+pub use crate::writer::Writer;
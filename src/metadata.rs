@@ -0,0 +1,439 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    error::{Error, Result},
+    infer::DataType,
+    json::{self, Value},
+    parser::Quotes,
+    schema::{Field, Schema},
+    Options,
+};
+
+/// A schema and parser options loaded from table metadata.
+///
+/// Both Frictionless Table Schema and W3C CSV on the Web (CSVW) metadata are
+/// supported. Types that have no equivalent `DataType` are loaded as strings,
+/// and constraints that have no equivalent in a `Field` are ignored.
+///
+/// ```
+/// use dsv::{DataType, Dsv, Metadata};
+///
+/// let json = r#"{
+///     "dialect": {"delimiter": ";"},
+///     "schema": {
+///         "fields": [
+///             {"name": "fruit", "type": "string"},
+///             {"name": "count", "type": "integer", "constraints": {"minimum": 0}}
+///         ],
+///         "primaryKey": "fruit"
+///     }
+/// }"#;
+/// let metadata = Metadata::from_frictionless(json).unwrap();
+/// assert_eq!(metadata.schema.fields[1].data_type, DataType::Integer);
+///
+/// let dsv = Dsv::from_str_with_options("fruit;count\napples;-2", metadata.options).unwrap();
+/// assert_eq!(dsv.validate(&metadata.schema).unwrap().len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    pub schema: Schema,
+    pub options: Options,
+}
+
+impl Metadata {
+    /// Load a Frictionless Table Schema, or a Data Resource containing a
+    /// `schema` and optionally a `dialect`.
+    ///
+    /// The schema's `missingValues` and the dialect's `delimiter`, `quoteChar`,
+    /// `header`, and `headerRows` are mapped onto the options, and each field's
+    /// `decimalChar` and `groupChar` onto its number format. As in the
+    /// specification, tables have headers unless the dialect says otherwise.
+    pub fn from_frictionless(text: &str) -> Result<Metadata> {
+        let root = json::parse(text)?;
+        let table_schema = root.get("schema").unwrap_or(&root);
+        let fields = table_schema
+            .get("fields")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("a table schema must have `fields`"))?;
+
+        let mut unique_keys = vec![];
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let name = field
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| invalid("a field must have a `name`"))?;
+                let data_type = match field.get("type").and_then(Value::as_str) {
+                    Some("integer") => DataType::Integer,
+                    Some("number") => DataType::Float,
+                    Some("boolean") => DataType::Boolean,
+                    Some("date") => DataType::Date,
+                    Some("datetime") => DataType::DateTime,
+                    _ => DataType::String,
+                };
+                let mut field_ = Field::new(name, data_type);
+                let char_of = |key| {
+                    field
+                        .get(key)
+                        .and_then(Value::as_str)
+                        .and_then(|s| s.chars().next())
+                };
+                if let Some(decimal) = char_of("decimalChar") {
+                    field_.number_format.decimal = decimal;
+                }
+                field_.number_format.grouping = char_of("groupChar");
+                let Some(constraints) = field.get("constraints") else {
+                    return Ok(field_);
+                };
+                let get = |key| constraints.get(key);
+                field_.required = get("required").and_then(Value::as_bool) == Some(true);
+                field_.pattern = get("pattern").and_then(Value::as_str).map(Into::into);
+                field_.minimum = get("minimum").and_then(Value::as_f64);
+                field_.maximum = get("maximum").and_then(Value::as_f64);
+                if let Some(values) = get("enum").and_then(Value::as_array) {
+                    field_.values = Some(texts(values, "enum")?);
+                }
+                if get("unique").and_then(Value::as_bool) == Some(true) {
+                    unique_keys.push(vec![name.to_string()]);
+                }
+                Ok(field_)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut schema = Schema {
+            fields,
+            unique_keys,
+        };
+        primary_key(&mut schema, table_schema.get("primaryKey"))?;
+
+        let mut options = Options {
+            headers: true,
+            null_values: null_values(table_schema.get("missingValues"), "missingValues")?,
+            ..Default::default()
+        };
+        if let Some(dialect) = root.get("dialect") {
+            dialect_options(dialect, &mut options)?;
+            if let Some(rows) = dialect.get("headerRows").and_then(Value::as_array) {
+                match rows {
+                    [] => options.headers = false,
+                    [row] => {
+                        let row = row
+                            .as_usize()
+                            .filter(|&row| row >= 1)
+                            .ok_or_else(|| invalid("`headerRows` must contain row numbers"))?;
+                        options.headers = true;
+                        options.skip_rows = row - 1;
+                    }
+                    _ => return Err(invalid("only one header row is supported")),
+                }
+            }
+        }
+
+        Ok(Metadata { schema, options })
+    }
+
+    /// Load CSVW metadata describing a table, or a table group, in which case
+    /// the first table is used.
+    ///
+    /// Columns are named by their `name`, or otherwise their first title.
+    /// Virtual columns are skipped. The table schema's `null` and the dialect's
+    /// `delimiter`, `quoteChar`, `header`, `headerRowCount`, and `skipRows` are
+    /// mapped onto the options.
+    pub fn from_csvw(text: &str) -> Result<Metadata> {
+        let root = json::parse(text)?;
+        let table = match root.get("tables").and_then(Value::as_array) {
+            Some(tables) => tables
+                .first()
+                .ok_or_else(|| invalid("a table group must have a table"))?,
+            None => &root,
+        };
+        let table_schema = table
+            .get("tableSchema")
+            .ok_or_else(|| invalid("a table must have a `tableSchema`"))?;
+        let columns = table_schema
+            .get("columns")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("a table schema must have `columns`"))?;
+
+        let mut fields = vec![];
+        for column in columns {
+            if column.get("virtual").and_then(Value::as_bool) == Some(true) {
+                continue;
+            }
+            let name = match (column.get("name"), column.get("titles")) {
+                (Some(name), _) => name.as_str(),
+                (None, Some(Value::Array(titles))) => titles.first().and_then(Value::as_str),
+                (None, Some(title)) => title.as_str(),
+                (None, None) => None,
+            };
+            let name = name.ok_or_else(|| invalid("a column must have a `name` or `titles`"))?;
+
+            let datatype = column.get("datatype");
+            let base = match datatype {
+                Some(Value::String(base)) => base.as_str(),
+                Some(datatype) => datatype
+                    .get("base")
+                    .and_then(Value::as_str)
+                    .unwrap_or("string"),
+                None => "string",
+            };
+            let data_type = csvw_type(base);
+            let mut field = Field::new(name, data_type);
+            field.required = column.get("required").and_then(Value::as_bool) == Some(true);
+            if let Some(datatype @ Value::Object(_)) = datatype {
+                let get = |keys: [&str; 2]| keys.iter().find_map(|&key| datatype.get(key));
+                field.minimum = get(["minimum", "minInclusive"]).and_then(Value::as_f64);
+                field.maximum = get(["maximum", "maxInclusive"]).and_then(Value::as_f64);
+                // the format of other types is a picture string, not a pattern
+                if data_type == DataType::String {
+                    field.pattern = datatype
+                        .get("format")
+                        .and_then(Value::as_str)
+                        .map(Into::into);
+                }
+            }
+            fields.push(field);
+        }
+
+        let mut schema = Schema::new(fields);
+        primary_key(&mut schema, table_schema.get("primaryKey"))?;
+
+        let mut options = Options {
+            headers: true,
+            null_values: null_values(table_schema.get("null"), "null")?,
+            ..Default::default()
+        };
+        if let Some(dialect) = table.get("dialect").or_else(|| root.get("dialect")) {
+            dialect_options(dialect, &mut options)?;
+            if let Some(count) = dialect.get("headerRowCount") {
+                match count.as_usize() {
+                    Some(0) => options.headers = false,
+                    Some(1) => options.headers = true,
+                    _ => return Err(invalid("only one header row is supported")),
+                }
+            }
+            if let Some(rows) = dialect.get("skipRows") {
+                options.skip_rows = rows
+                    .as_usize()
+                    .ok_or_else(|| invalid("`skipRows` must be a number of rows"))?;
+            }
+        }
+
+        Ok(Metadata { schema, options })
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::Metadata(message.into())
+}
+
+/// Get the text of each value in an array.
+fn texts(values: &[Value], key: &str) -> Result<Vec<String>> {
+    values
+        .iter()
+        .map(|value| value.as_text().map(Into::into))
+        .collect::<Option<_>>()
+        .ok_or_else(|| Error::Metadata(format!("`{key}` must contain strings or numbers")))
+}
+
+/// Get the values that represent a missing value, which are a value or list of
+/// values that default to the empty string.
+fn null_values(values: Option<&Value>, key: &str) -> Result<Vec<String>> {
+    match values {
+        None => Ok(vec![String::new()]),
+        Some(Value::Array(values)) => texts(values, key),
+        Some(value) => texts(core::slice::from_ref(value), key),
+    }
+}
+
+/// Add a primary key, which is a field name or list of names, as a unique key
+/// whose fields are required.
+fn primary_key(schema: &mut Schema, key: Option<&Value>) -> Result<()> {
+    let key = match key {
+        None => return Ok(()),
+        Some(Value::String(name)) => vec![name.clone()],
+        Some(Value::Array(names)) => texts(names, "primaryKey")?,
+        Some(_) => return Err(invalid("`primaryKey` must be a name or list of names")),
+    };
+    for field in &mut schema.fields {
+        if key.contains(&field.name) {
+            field.required = true;
+        }
+    }
+    schema.unique_keys.push(key);
+    Ok(())
+}
+
+/// Apply the dialect properties shared by both formats.
+fn dialect_options(dialect: &Value, options: &mut Options) -> Result<()> {
+    if let Some(delimiter) = dialect.get("delimiter") {
+        options.delimiter = match delimiter.as_str().map(str::as_bytes) {
+            Some(&[delimiter]) => delimiter,
+            _ => return Err(invalid("the delimiter must be a single byte")),
+        };
+    }
+    if let Some(quote) = dialect.get("quoteChar") {
+        options.quotes = match quote {
+            Value::Null => Quotes::Insignificant,
+            Value::String(quote) if quote == "\"" => Quotes::Significant,
+            _ => return Err(invalid("the quote character must be `\"`")),
+        };
+    }
+    if dialect.get("doubleQuote").and_then(Value::as_bool) == Some(false) {
+        return Err(invalid("quotes must be escaped by doubling them"));
+    }
+    if let Some(header) = dialect.get("header").and_then(Value::as_bool) {
+        options.headers = header;
+    }
+    Ok(())
+}
+
+fn csvw_type(base: &str) -> DataType {
+    match base {
+        "integer" | "int" | "long" | "short" | "byte" | "nonNegativeInteger"
+        | "positiveInteger" | "nonPositiveInteger" | "negativeInteger" | "unsignedLong"
+        | "unsignedInt" | "unsignedShort" | "unsignedByte" => DataType::Integer,
+        "number" | "decimal" | "double" | "float" => DataType::Float,
+        "boolean" => DataType::Boolean,
+        "date" => DataType::Date,
+        "datetime" | "dateTime" | "dateTimeStamp" => DataType::DateTime,
+        _ => DataType::String,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{convert::NumberFormat, Dsv};
+
+    #[test]
+    fn frictionless() -> Result<()> {
+        let json = r#"{
+            "fields": [
+                {"name": "id", "type": "integer"},
+                {"name": "price", "type": "number", "decimalChar": ",",
+                 "constraints": {"required": true, "minimum": 0, "maximum": 1e3}},
+                {"name": "country", "type": "string",
+                 "constraints": {"enum": ["DE", "FR"], "pattern": "[A-Z]+", "unique": true}},
+                {"name": "tags", "type": "array"}
+            ],
+            "primaryKey": ["id"],
+            "missingValues": ["", "-"]
+        }"#;
+        let metadata = Metadata::from_frictionless(json)?;
+        let fields = &metadata.schema.fields;
+        assert_eq!(fields[0].data_type, DataType::Integer);
+        assert!(fields[0].required);
+        assert_eq!(fields[1].data_type, DataType::Float);
+        assert_eq!(
+            (fields[1].minimum, fields[1].maximum),
+            (Some(0.0), Some(1000.0))
+        );
+        assert_eq!(fields[2].values, Some(vec!["DE".into(), "FR".into()]));
+        assert_eq!(fields[2].pattern.as_deref(), Some("[A-Z]+"));
+        assert_eq!(fields[3].data_type, DataType::String);
+        assert_eq!(metadata.schema.unique_keys, [["country"], ["id"]]);
+        assert!(metadata.options.headers);
+        assert_eq!(metadata.options.null_values, ["", "-"]);
+        assert_eq!(fields[0].number_format, NumberFormat::default());
+        assert_eq!(fields[1].number_format.decimal, ',');
+        Ok(())
+    }
+
+    #[test]
+    fn frictionless_separators() -> Result<()> {
+        let json = r#"{
+            "fields": [
+                {"name": "de", "type": "number", "decimalChar": ",", "groupChar": "."},
+                {"name": "en", "type": "number", "groupChar": ","}
+            ]
+        }"#;
+        let metadata = Metadata::from_frictionless(json)?;
+        let dsv = Dsv::from_str_with_options("de,en\n\"1.234,5\",\"1,234.5\"", metadata.options)?;
+        assert!(dsv.validate(&metadata.schema)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn frictionless_dialect() -> Result<()> {
+        let json = r#"{
+            "schema": {"fields": [{"name": "a"}]},
+            "dialect": {"delimiter": "\t", "quoteChar": "\"", "headerRows": [3]}
+        }"#;
+        let options = Metadata::from_frictionless(json)?.options;
+        assert_eq!(options.delimiter, b'\t');
+        assert!(options.headers);
+        assert_eq!(options.skip_rows, 2);
+        assert_eq!(options.null_values, [""]);
+
+        let json = r#"{"schema": {"fields": []}, "dialect": {"header": false}}"#;
+        assert!(!Metadata::from_frictionless(json)?.options.headers);
+        Ok(())
+    }
+
+    #[test]
+    fn csvw() -> Result<()> {
+        let json = r#"{
+            "@context": "http://www.w3.org/ns/csvw",
+            "tables": [{
+                "url": "prices.csv",
+                "tableSchema": {
+                    "columns": [
+                        {"name": "id", "datatype": "long"},
+                        {"titles": ["Price", "Preis"], "required": true,
+                         "datatype": {"base": "decimal", "minInclusive": 0}},
+                        {"titles": "Code", "datatype": {"base": "string", "format": "[A-Z]{2}"}},
+                        {"name": "when", "datatype": {"base": "date", "format": "dd.MM.yyyy"}},
+                        {"name": "source", "virtual": true}
+                    ],
+                    "primaryKey": "id",
+                    "null": "NA"
+                }
+            }],
+            "dialect": {"delimiter": ";", "quoteChar": null, "skipRows": 1, "headerRowCount": 1}
+        }"#;
+        let metadata = Metadata::from_csvw(json)?;
+        let fields = &metadata.schema.fields;
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[0].data_type, DataType::Integer);
+        assert_eq!(fields[1].name, "Price");
+        assert_eq!(fields[1].minimum, Some(0.0));
+        assert!(fields[1].required);
+        assert_eq!(fields[2].name, "Code");
+        assert_eq!(fields[2].pattern.as_deref(), Some("[A-Z]{2}"));
+        assert_eq!(fields[3].pattern, None);
+        assert_eq!(metadata.schema.unique_keys, [["id"]]);
+
+        let options = metadata.options;
+        assert_eq!(options.delimiter, b';');
+        assert_eq!(options.quotes, Quotes::Insignificant);
+        assert_eq!(options.skip_rows, 1);
+        assert!(options.headers);
+        assert_eq!(options.null_values, ["NA"]);
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported() {
+        let cases = [
+            r#"{"fields": [{"type": "string"}]}"#,
+            r#"{"schema": {"fields": []}, "dialect": {"delimiter": "::"}}"#,
+            r#"{"schema": {"fields": []}, "dialect": {"quoteChar": "'"}}"#,
+            r#"{"schema": {"fields": []}, "dialect": {"headerRows": [1, 2]}}"#,
+            r#"{"fields": [], "primaryKey": 1}"#,
+            r#"{"fields": "#,
+        ];
+        for json in cases {
+            let result = Metadata::from_frictionless(json);
+            assert!(matches!(result, Err(Error::Metadata(_))), "{json}");
+        }
+        let result = Metadata::from_csvw(r#"{"tables": []}"#);
+        assert!(matches!(result, Err(Error::Metadata(_))));
+    }
+}
@@ -0,0 +1,37 @@
+use std::{fs::File, path::Path};
+
+use crate::error::{Error, Result};
+
+/// A read-only memory mapping of a DSV file.
+///
+/// The mapping is validated as UTF-8 when it is opened, after which records
+/// can be parsed lazily from it using `Reader::from_mmap` without copying the
+/// file into memory first.
+pub struct Mmap {
+    map: memmap2::Mmap,
+}
+
+impl Mmap {
+    /// Map the file at the specified path into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other
+    /// process, for as long as the mapping exists. The contents are handed out
+    /// as a `&str` that is assumed to stay unchanged and valid UTF-8, so
+    /// changing the file underneath the mapping is undefined behavior.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> Result<Mmap> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only, and the caller guarantees that the
+        // file is not modified while it is mapped.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        std::str::from_utf8(&map).map_err(|_| Error::Encoding)?;
+        Ok(Mmap { map })
+    }
+
+    /// Get the contents of the mapping.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the mapping was validated as UTF-8 when it was opened.
+        unsafe { std::str::from_utf8_unchecked(&self.map) }
+    }
+}
@@ -1,8 +1,12 @@
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+use core::mem;
+
 use crate::error::{Error, Result};
 use crate::lexer::Token;
 use crate::{lexer::Lexer, Dsv};
 
 /// Treatment of double quotes.
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Quotes {
     /// Quotes have no special meaning and are considered part of the value.
     Insignificant,
@@ -12,6 +16,7 @@ pub enum Quotes {
 }
 
 /// Options for parsing a DSV file.
+#[derive(Clone, Debug)]
 pub struct Options {
     /// The delimiter that separates fields.
     ///
@@ -21,6 +26,25 @@ pub struct Options {
     ///
     /// Default: `Quotes::Significant`.
     pub quotes: Quotes,
+    /// The columns to keep from each record, in order.
+    ///
+    /// Default: `None`, which keeps every column.
+    pub projection: Option<Vec<usize>>,
+    /// Whether the first record contains the headers of the table.
+    ///
+    /// Default: `false`.
+    pub headers: bool,
+    /// The number of lines to skip at the start of the input, such as a title
+    /// above the table. Quotes have no special meaning in skipped lines.
+    ///
+    /// Default: `0`.
+    pub skip_rows: usize,
+    /// Values that represent a missing value, such as `NA` or `\N`. Include
+    /// the empty string to treat empty fields as null. Only unquoted fields
+    /// are null, so `""` remains an empty string.
+    ///
+    /// Default: empty, so that no field is null.
+    pub null_values: Vec<String>,
 }
 
 impl Default for Options {
@@ -28,65 +52,179 @@ impl Default for Options {
         Self {
             delimiter: b',',
             quotes: Quotes::Significant,
+            projection: None,
+            headers: false,
+            skip_rows: 0,
+            null_values: vec![],
         }
     }
 }
 
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
+    projection: Option<Vec<usize>>,
+    headers: bool,
+    null_values: Vec<String>,
+    num_expected_fields: Option<usize>,
+    /// Whether each field of the last record was quoted.
+    quoted: Vec<bool>,
 }
 
 impl<'a> Parser<'a> {
+    pub fn new(text: &'a str, options: Options) -> Self {
+        let mut lexer = Lexer::new(text, options.delimiter, options.quotes);
+        lexer.seek(skip_rows(text.as_bytes(), options.skip_rows));
+        Self {
+            lexer,
+            projection: options.projection,
+            headers: options.headers,
+            null_values: options.null_values,
+            num_expected_fields: None,
+            quoted: vec![],
+        }
+    }
+
     pub fn from_str(text: &'a str) -> Result<Dsv> {
         Parser::from_str_with_options(text, Options::default())
     }
 
     pub fn from_str_with_options(text: &'a str, options: Options) -> Result<Dsv> {
-        let lexer = Lexer::new(text, options.delimiter, options.quotes);
-        let mut parser = Self { lexer };
+        let mut parser = Parser::new(text, options);
         parser.dsv()
     }
 
+    /// Get the underlying input text.
+    pub fn text(&self) -> &'a str {
+        self.lexer.text()
+    }
+
+    /// Move to the specified byte offset, which must be the start of a record.
+    pub fn seek(&mut self, pos: usize) {
+        self.lexer.seek(pos);
+    }
+
+    /// Parse the next record, borrowing fields from the input where possible.
+    ///
+    /// Returns `None` at the end of the input.
+    pub fn next_record(&mut self) -> Result<Option<Vec<Cow<'a, str>>>> {
+        if self.lexer.peek()?.is_none() {
+            return Ok(None);
+        }
+
+        let record = self.record()?;
+        match self.num_expected_fields {
+            None => self.num_expected_fields = Some(record.len()),
+            Some(len) if record.len() != len => return Err(Error::Parse),
+            _ => {}
+        }
+
+        match &self.projection {
+            Some(projection) => Ok(Some(project(record, projection)?)),
+            None => Ok(Some(record)),
+        }
+    }
+
+    /// Parse the next record, with `None` in place of null fields.
+    ///
+    /// Returns `None` at the end of the input.
+    pub fn next_record_opt(&mut self) -> Result<Option<Vec<Option<Cow<'a, str>>>>> {
+        let Some(record) = self.next_record()? else {
+            return Ok(None);
+        };
+        let quoted = match &self.projection {
+            Some(projection) => project(mem::take(&mut self.quoted), projection)?,
+            None => mem::take(&mut self.quoted),
+        };
+        let record = record
+            .into_iter()
+            .zip(quoted)
+            .map(|(value, quoted)| {
+                let null = !quoted && self.null_values.iter().any(|null| *null == value);
+                (!null).then_some(value)
+            })
+            .collect();
+        Ok(Some(record))
+    }
+
     fn dsv(&mut self) -> Result<Dsv> {
         let mut dsv = Dsv::new();
-        let mut num_expected_fields = None;
-
-        while let Some(_token) = self.lexer.peek()? {
-            let record = self.record()?;
-            match num_expected_fields {
-                None => num_expected_fields = Some(record.len()),
-                Some(len) if record.len() != len => return Err(Error::Parse),
-                _ => {}
-            }
-            dsv.records.push(record);
+        while let Some(record) = self.next_record_opt()? {
+            dsv.push_parsed(record, self.headers);
         }
-
         Ok(dsv)
     }
 
-    fn record(&mut self) -> Result<Vec<String>> {
+    fn record(&mut self) -> Result<Vec<Cow<'a, str>>> {
         let mut record = vec![];
-        while let Some(token) = self.lexer.next()? {
+        self.quoted.clear();
+        let quotes = self.lexer.quotes();
+        loop {
+            let start = self.lexer.pos();
+            let Some(token) = self.lexer.next()? else {
+                break;
+            };
             match (token, self.lexer.peek()?) {
                 (Token::Newline, _) => break,
                 // disallow consecutive values
                 (Token::Value(_), Some(Token::Value(_))) => return Err(Error::Parse),
-                (Token::Value(value), _) => record.push(value),
-                // infer empty value between consecutive delimiters
-                (Token::Delimiter, Some(Token::Delimiter)) => record.push(String::new()),
-                // infer empty value at newline or end of file
-                (Token::Delimiter, Some(Token::Newline)) | (Token::Delimiter, None) => {
-                    record.push(String::new())
+                (Token::Value(value), _) => {
+                    let quoted = quotes == Quotes::Significant
+                        && self.lexer.text().as_bytes()[start] == b'"';
+                    record.push(value);
+                    self.quoted.push(quoted);
+                }
+                (Token::Delimiter, next) => {
+                    // infer empty value at beginning of record
+                    if record.is_empty() {
+                        record.push(Cow::Borrowed(""));
+                        self.quoted.push(false);
+                    }
+                    // infer empty value between consecutive delimiters and at
+                    // newline or end of file
+                    if matches!(next, Some(Token::Delimiter) | Some(Token::Newline) | None) {
+                        record.push(Cow::Borrowed(""));
+                        self.quoted.push(false);
+                    }
                 }
-                // infer empty value at beginning of record
-                (Token::Delimiter, _) if record.is_empty() => record.push(String::new()),
-                _ => {}
             }
         }
         Ok(record)
     }
 }
 
+/// Get the byte offset following the specified number of lines.
+pub(crate) fn skip_rows(input: &[u8], rows: usize) -> usize {
+    if rows == 0 {
+        return 0;
+    }
+    input
+        .iter()
+        .enumerate()
+        .filter(|(_, &b)| b == b'\n')
+        .nth(rows - 1)
+        .map_or(input.len(), |(ix, _)| ix + 1)
+}
+
+/// Select the specified columns from a record, in order.
+///
+/// Fields are moved out of the record rather than copied unless they are
+/// selected more than once.
+pub(crate) fn project<T: Clone + Default>(
+    mut record: Vec<T>,
+    projection: &[usize],
+) -> Result<Vec<T>> {
+    let mut projected = Vec::with_capacity(projection.len());
+    for (ix, &column) in projection.iter().enumerate() {
+        let field = record.get_mut(column).ok_or(Error::OutOfBounds)?;
+        if projection[ix + 1..].contains(&column) {
+            projected.push(field.clone());
+        } else {
+            projected.push(mem::take(field));
+        }
+    }
+    Ok(projected)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +286,21 @@ mod tests {
         assert_eq!(dsv, expected);
     }
 
+    #[test]
+    fn only_delimiters() {
+        let text = ",,";
+        let dsv = Parser::from_str(text).unwrap();
+        let mut expected = Dsv::new();
+        expected.records.push(vec!["".into(), "".into(), "".into()]);
+        assert_eq!(dsv, expected);
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        let text = "foo,\"bar\nbaz";
+        assert!(Parser::from_str(text).is_err());
+    }
+
     #[test]
     fn mismatched_field_count() {
         let text = "foo,bar\nbaz";
@@ -155,6 +308,109 @@ mod tests {
         assert!(dsv.is_err());
     }
 
+    #[test]
+    fn projection() {
+        let text = "foo,bar,baz\nqux,\"b\"\"ux\",quux";
+        let options = Options {
+            projection: Some(vec![2, 0, 2]),
+            ..Default::default()
+        };
+        let dsv = Parser::from_str_with_options(text, options).unwrap();
+        let mut expected = Dsv::new();
+        expected
+            .records
+            .push(vec!["baz".into(), "foo".into(), "baz".into()]);
+        expected
+            .records
+            .push(vec!["quux".into(), "qux".into(), "quux".into()]);
+        assert_eq!(dsv, expected);
+    }
+
+    #[test]
+    fn projection_out_of_bounds() {
+        let text = "foo,bar";
+        let options = Options {
+            projection: Some(vec![2]),
+            ..Default::default()
+        };
+        let dsv = Parser::from_str_with_options(text, options);
+        assert!(dsv.is_err());
+    }
+
+    #[test]
+    fn headers() {
+        let text = "name,count\nfoo,1";
+        let options = Options {
+            headers: true,
+            ..Default::default()
+        };
+        let dsv = Parser::from_str_with_options(text, options).unwrap();
+        let mut expected = Dsv::new();
+        expected.headers = Some(vec!["name".into(), "count".into()]);
+        expected.records.push(vec!["foo".into(), "1".into()]);
+        assert_eq!(dsv, expected);
+    }
+
+    #[test]
+    fn skip_rows() -> Result<()> {
+        let text = "Report \"2024\nname,count\nfoo,1";
+        let options = Options {
+            headers: true,
+            skip_rows: 1,
+            ..Default::default()
+        };
+        let dsv = Parser::from_str_with_options(text, options.clone())?;
+        assert_eq!(dsv.headers().unwrap(), ["name", "count"]);
+        assert_eq!(dsv.records(), [["foo", "1"]]);
+
+        let options = Options {
+            skip_rows: 5,
+            ..options
+        };
+        assert_eq!(Parser::from_str_with_options(text, options)?, Dsv::new());
+        Ok(())
+    }
+
+    #[test]
+    fn null_values() -> Result<()> {
+        let text = "a,,\"\",NA,\"NA\"\n,b,,c,NA";
+        let options = Options {
+            null_values: vec!["".into(), "NA".into()],
+            ..Default::default()
+        };
+        let mut parser = Parser::new(text, options.clone());
+        let record = parser.next_record_opt()?.unwrap();
+        assert_eq!(
+            record,
+            [
+                Some("a".into()),
+                None,
+                Some("".into()),
+                None,
+                Some("NA".into())
+            ]
+        );
+        let record = parser.next_record_opt()?.unwrap();
+        assert_eq!(
+            record,
+            [None, Some("b".into()), None, Some("c".into()), None]
+        );
+        assert_eq!(parser.next_record_opt()?, None);
+
+        let options = Options {
+            projection: Some(vec![4, 1]),
+            ..options
+        };
+        let mut parser = Parser::new(text, options);
+        let record = parser.next_record_opt()?.unwrap();
+        assert_eq!(record, [Some("NA".into()), None]);
+
+        let mut parser = Parser::new(text, Options::default());
+        let record = parser.next_record_opt()?.unwrap();
+        assert!(record.iter().all(Option::is_some));
+        Ok(())
+    }
+
     #[test]
     fn tab_delim() {
         let text = "foo\tbar";
@@ -0,0 +1,420 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    diff::{Diff, DiffKind},
+    error::{Error, Result},
+    Dsv,
+};
+
+/// Changes to the records of a table, matched by key, that can be stored as a
+/// table and applied to another copy of the original.
+///
+/// A patch stored as a table has an `op` column, one column for each key
+/// column, and `column`, `old`, and `new` columns. Each record is an operation
+/// on one field, where `op` is one of `add`, `remove`, or `change`. The `old`
+/// field of an added record and the `new` field of a removed one are empty,
+/// and null values are stored as null fields. To keep nulls apart from empty
+/// values when the stored patch is written as text, write and read it with
+/// `Options::null_values`.
+///
+/// ```
+/// use dsv::{Dsv, Options, Patch};
+///
+/// let read = |text| {
+///     let options = Options { headers: true, ..Default::default() };
+///     Dsv::from_str_with_options(text, options).unwrap()
+/// };
+/// let old = read("id,price\n1,1.5\n2,3\n");
+/// let new = read("id,price\n1,2\n3,4\n");
+/// let patch = Patch::from_diff(&dsv::diff(&old, &new, &["id"]).unwrap()).unwrap();
+///
+/// let stored = patch.to_dsv();
+/// assert_eq!(stored.headers().unwrap(), ["op", "id", "column", "old", "new"]);
+/// assert_eq!(stored[0], ["change", "1", "price", "1.5", "2"]);
+///
+/// let mut copy = read("id,price\n1,1.5\n2,3\n");
+/// copy.apply_patch(&Patch::from_dsv(&stored).unwrap()).unwrap();
+/// assert_eq!(copy, new);
+/// ```
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Patch {
+    /// The names of the key columns.
+    pub key_columns: Vec<String>,
+    pub operations: Vec<PatchOperation>,
+}
+
+/// An operation on one field of a record.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PatchOperation {
+    pub kind: DiffKind,
+    /// The values of the key columns of the record, with `None` for null
+    /// fields.
+    pub key: Vec<Option<String>>,
+    /// The header of the column, or its index if there are no headers.
+    pub column: String,
+    /// The expected value of the field, with `None` for null, unless the
+    /// record is added.
+    pub old: Option<Option<String>>,
+    /// The value to set, with `None` for null, unless the record is removed.
+    pub new: Option<Option<String>>,
+}
+
+impl Patch {
+    /// Create a patch from the records of a diff.
+    ///
+    /// Fails if the diff compared records by order rather than by key, or if
+    /// the tables have different columns.
+    pub fn from_diff(diff: &Diff) -> Result<Patch> {
+        if diff.key_columns.is_empty() {
+            return Err(invalid("a patch requires key columns"));
+        }
+        if !diff.added_columns.is_empty() || !diff.removed_columns.is_empty() {
+            return Err(invalid("a patch can not add or remove columns"));
+        }
+        let operations = diff
+            .records
+            .iter()
+            .flat_map(|record| {
+                record.fields.iter().map(|field| PatchOperation {
+                    kind: record.kind,
+                    key: record.key.clone(),
+                    column: field.column.clone(),
+                    old: field.old.clone(),
+                    new: field.new.clone(),
+                })
+            })
+            .collect();
+        Ok(Patch {
+            key_columns: diff.key_columns.clone(),
+            operations,
+        })
+    }
+
+    /// Store the patch as a table.
+    pub fn to_dsv(&self) -> Dsv {
+        let mut headers = vec!["op".to_string()];
+        headers.extend(self.key_columns.iter().cloned());
+        headers.extend(["column", "old", "new"].map(String::from));
+
+        let records = self
+            .operations
+            .iter()
+            .map(|operation| {
+                let op = match operation.kind {
+                    DiffKind::Added => "add",
+                    DiffKind::Removed => "remove",
+                    DiffKind::Changed => "change",
+                };
+                // a value that the operation does not have is stored as empty
+                let value = |value: &Option<Option<String>>| {
+                    value.clone().unwrap_or_else(|| Some(String::new()))
+                };
+                let mut record = vec![Some(op.to_string())];
+                record.extend(operation.key.iter().cloned());
+                record.push(Some(operation.column.clone()));
+                record.push(value(&operation.old));
+                record.push(value(&operation.new));
+                record
+            })
+            .collect();
+
+        Dsv::from_opt(Some(headers), records)
+    }
+
+    /// Load a patch that was stored as a table.
+    ///
+    /// Fails if the table does not have the headers of a patch or an operation
+    /// is not recognized.
+    pub fn from_dsv(dsv: &Dsv) -> Result<Patch> {
+        let headers = dsv.headers().unwrap_or_default();
+        let width = headers.len();
+        let valid =
+            width > 4 && headers[0] == "op" && headers[width - 3..] == ["column", "old", "new"];
+        if !valid {
+            return Err(invalid(
+                "a patch must have `op`, key, `column`, `old`, and `new` headers",
+            ));
+        }
+
+        let operations = (0..dsv.records.len())
+            .map(|ix| {
+                let field = |column| dsv.field_opt(ix, column).map(String::from);
+                let record = &dsv.records[ix];
+                let kind = match record[0].as_str() {
+                    "add" => DiffKind::Added,
+                    "remove" => DiffKind::Removed,
+                    "change" => DiffKind::Changed,
+                    op => return Err(invalid(&format!("unknown operation `{op}`"))),
+                };
+                let value = |column, present: bool| present.then(|| field(column));
+                Ok(PatchOperation {
+                    kind,
+                    key: (1..width - 3).map(field).collect(),
+                    column: record[width - 3].clone(),
+                    old: value(width - 2, kind != DiffKind::Added),
+                    new: value(width - 1, kind != DiffKind::Removed),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Patch {
+            key_columns: headers[1..width - 3].to_vec(),
+            operations,
+        })
+    }
+}
+
+impl Dsv {
+    /// Apply a patch, matching records by the key columns of the patch.
+    ///
+    /// The patch is checked in full before any record is modified, and fails
+    /// with `Error::Conflict` at the first operation that does not match this
+    /// table: a changed or removed field whose value is not the expected old
+    /// value, a key that is not found, or an added record whose key already
+    /// exists. A null field only matches an expected null, and null values are
+    /// set as null. Added records are appended in the order of the patch, and
+    /// fields that the patch does not set are empty. Fails with
+    /// `Error::Duplicate` if a key appears more than once in this table.
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<()> {
+        let keys = patch
+            .key_columns
+            .iter()
+            .map(|name| self.column_by_name(name))
+            .collect::<Result<Vec<_>>>()?;
+        let mut lookup = BTreeMap::new();
+        for ix in 0..self.records.len() {
+            let key: Vec<Option<&str>> = keys.iter().map(|&c| self.field_opt(ix, c)).collect();
+            if lookup.insert(key, ix).is_some() {
+                return Err(Error::Duplicate { record: ix });
+            }
+        }
+
+        let mut changes = vec![];
+        let mut removed = vec![false; self.records.len()];
+        let mut added: Vec<Vec<Option<String>>> = vec![];
+        let mut pending: BTreeMap<Vec<Option<String>>, usize> = BTreeMap::new();
+        for (ix, operation) in patch.operations.iter().enumerate() {
+            let conflict = || Error::Conflict { operation: ix };
+            let column = self.column_by_name(&operation.column)?;
+            let key: Vec<Option<&str>> = operation.key.iter().map(Option::as_deref).collect();
+            if key.len() != keys.len() {
+                return Err(invalid("a key does not match the key columns"));
+            }
+            let record = lookup.get(&key).copied();
+
+            if operation.kind == DiffKind::Added {
+                if record.is_some() {
+                    return Err(conflict());
+                }
+                let position = pending.get(&operation.key).copied();
+                let position = position.unwrap_or_else(|| {
+                    let mut fields = vec![Some(String::new()); self.width()];
+                    for (&c, value) in keys.iter().zip(&operation.key) {
+                        fields[c] = value.clone();
+                    }
+                    added.push(fields);
+                    pending.insert(operation.key.clone(), added.len() - 1);
+                    added.len() - 1
+                });
+                added[position][column] = new(operation);
+                continue;
+            }
+
+            let record = record.ok_or_else(conflict)?;
+            let old = operation.old.as_ref().map_or(Some(""), Option::as_deref);
+            if self.field_opt(record, column) != old {
+                return Err(conflict());
+            }
+            match operation.kind {
+                DiffKind::Removed => removed[record] = true,
+                _ => changes.push((record, column, new(operation))),
+            }
+        }
+
+        for (record, column, value) in changes {
+            self.set_field(record, column, value)?;
+        }
+        if removed.contains(&true) {
+            let retain: Vec<bool> = removed.iter().map(|&removed| !removed).collect();
+            self.retain_indexes(&retain);
+        }
+        for fields in added {
+            self.push_record_opt(fields)?;
+        }
+        Ok(())
+    }
+
+    /// Get the index of a column, given its header or, if there are no
+    /// headers, its index as text.
+    fn column_by_name(&self, name: &str) -> Result<usize> {
+        let column = match self.headers() {
+            Some(headers) => headers.iter().position(|header| header == name),
+            None => name.parse().ok().filter(|&c| c < self.width()),
+        };
+        column.ok_or_else(|| Error::UnknownColumn(name.into()))
+    }
+}
+
+/// Get the value that an operation sets, which is empty if it has none.
+fn new(operation: &PatchOperation) -> Option<String> {
+    operation.new.clone().unwrap_or_else(|| Some(String::new()))
+}
+
+fn invalid(message: &str) -> Error {
+    Error::Patch(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diff, Options};
+
+    fn read(text: &str) -> Result<Dsv> {
+        let options = Options {
+            headers: true,
+            ..Default::default()
+        };
+        Dsv::from_str_with_options(text, options)
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let old = read("id,name,price\n1,apples,1.5\n2,pears,3\n3,kiwis,1\n")?;
+        let new = read("id,name,price\n1,apples,2\n3,kiwis,1\n4,plums,5\n")?;
+        let patch = Patch::from_diff(&diff(&old, &new, &["id"])?)?;
+        assert_eq!(patch.operations.len(), 7);
+
+        let loaded = Patch::from_dsv(&patch.to_dsv())?;
+        assert_eq!(loaded, patch);
+
+        let mut patched = old;
+        patched.apply_patch(&loaded)?;
+        assert_eq!(patched, new);
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let options = Options {
+            headers: true,
+            null_values: vec!["NA".into()],
+            ..Default::default()
+        };
+        let read = |text| Dsv::from_str_with_options(text, options.clone());
+        let old = read("id,price\n1,NA\nNA,2\n,3\n")?;
+        let new = read("id,price\n1,\nNA,NA\n,3\n5,NA\n")?;
+        let patch = Patch::from_diff(&diff(&old, &new, &["id"])?)?;
+        let stored = patch.to_dsv();
+        assert_eq!(Patch::from_dsv(&stored)?, patch);
+        // values that an operation does not have are empty rather than null
+        for record in stored.iter() {
+            let (old, new) = (record.get_opt("old")?, record.get_opt("new")?);
+            match record.get("op").unwrap() {
+                "add" => assert_eq!(old, Some("")),
+                "change" => assert!(old.is_none() != new.is_none()),
+                _ => unreachable!(),
+            }
+        }
+
+        let mut patched = old;
+        patched.apply_patch(&patch)?;
+        assert_eq!(patched, new);
+
+        // an empty field does not match an expected null
+        let mut edited = read("id,price\n1,\nNA,2\n,3\n")?;
+        assert!(matches!(
+            edited.apply_patch(&patch),
+            Err(Error::Conflict { operation: 0 })
+        ));
+
+        #[cfg(feature = "std")]
+        {
+            let mut writer = crate::Writer::with_options(vec![], options.clone());
+            writer.write_dsv(&stored)?;
+            let text = String::from_utf8(writer.into_inner()).unwrap();
+            assert_eq!(Patch::from_dsv(&read(&text)?)?, patch);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn stored() -> Result<()> {
+        let old = read("id,note\n1,\"a,b\"\n")?;
+        let new = read("id,note\n1,\"\"\"c\"\"\"\n")?;
+        let patch = Patch::from_diff(&diff(&old, &new, &["id"])?)?;
+        let mut writer = crate::Writer::new(vec![]);
+        writer.write_dsv(&patch.to_dsv())?;
+        let text = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(Patch::from_dsv(&read(&text)?)?, patch);
+        Ok(())
+    }
+
+    #[test]
+    fn headerless() -> Result<()> {
+        let old = Dsv::from_str("a,1\nb,2")?;
+        let new = Dsv::from_str("a,1\nb,3\nc,4")?;
+        let patch = Patch::from_diff(&diff(&old, &new, &[0])?)?;
+        assert_eq!(patch.key_columns, ["0"]);
+        let mut patched = old;
+        patched.apply_patch(&patch)?;
+        assert_eq!(patched, new);
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts() -> Result<()> {
+        let old = read("id,price\n1,1.5\n2,3\n")?;
+        let new = read("id,price\n1,2\n3,4\n")?;
+        let patch = Patch::from_diff(&diff(&old, &new, &["id"])?)?;
+
+        // applying twice conflicts, and the first conflict leaves the table
+        // unchanged
+        let mut patched = old;
+        patched.apply_patch(&patch)?;
+        assert_eq!(patched, new);
+        assert!(matches!(
+            patched.apply_patch(&patch),
+            Err(Error::Conflict { operation: 0 })
+        ));
+        assert_eq!(patched, new);
+
+        let mut edited = read("id,price\n1,1.5\n2,3.5\n")?;
+        assert!(matches!(
+            edited.apply_patch(&patch),
+            Err(Error::Conflict { operation: 2 })
+        ));
+        let mut missing = read("id,price\n1,1.5\n")?;
+        assert!(matches!(
+            missing.apply_patch(&patch),
+            Err(Error::Conflict { operation: 1 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_patches() -> Result<()> {
+        let old = read("id,qty\n1,2\n")?;
+        let new = read("id,discount\n1,5\n")?;
+        let result = Patch::from_diff(&diff(&old, &new, &["id"])?);
+        assert!(matches!(result, Err(Error::Patch(_))));
+        let result = Patch::from_diff(&diff::diff_by_order(&old, &old)?);
+        assert!(matches!(result, Err(Error::Patch(_))));
+
+        for text in [
+            "id,column,old,new",
+            "op,id,column,old",
+            "op,id,column,old,new\nput,1,a,,b",
+        ] {
+            let result = Patch::from_dsv(&read(text)?);
+            assert!(matches!(result, Err(Error::Patch(_))), "{text}");
+        }
+        Ok(())
+    }
+}
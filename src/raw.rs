@@ -0,0 +1,267 @@
+use alloc::vec::Vec;
+
+use crate::{
+    error::{Error, Result},
+    parser::{skip_rows, Quotes},
+    Options,
+};
+
+/// The location of a field within the input.
+///
+/// For quoted fields, the span excludes the surrounding quotes but any escaped
+/// quotes within the field remain doubled. Use `Span::unescape` to iterate
+/// over the bytes of the value itself.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Span {
+    /// The byte offset at which the field starts.
+    pub start: usize,
+    /// The byte offset at which the field ends.
+    pub end: usize,
+    /// Whether the field contains escaped quotes.
+    pub escaped: bool,
+}
+
+impl Span {
+    /// Get the raw bytes of the field from the input.
+    pub fn bytes<'a>(&self, input: &'a [u8]) -> &'a [u8] {
+        &input[self.start..self.end]
+    }
+
+    /// Iterate over the bytes of the field value, collapsing escaped quotes.
+    pub fn unescape<'a>(&self, input: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+        let bytes = self.bytes(input);
+        let escaped = self.escaped;
+        let mut skip = false;
+        bytes.iter().copied().filter(move |&b| {
+            if escaped && b == b'"' {
+                skip = !skip;
+                return skip;
+            }
+            true
+        })
+    }
+}
+
+/// An allocation-free reader over delimiter-separated values.
+///
+/// A `RawReader` does not build records itself. Instead, it writes the span of
+/// each field into a caller-provided buffer, which makes it suitable for
+/// targets without an allocator. It accepts the same syntax as the parser.
+pub struct RawReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    delimiter: u8,
+    quotes: Quotes,
+    projection: Option<Vec<usize>>,
+    num_expected_fields: Option<usize>,
+}
+
+impl<'a> RawReader<'a> {
+    /// Create a raw reader over an input.
+    pub fn new(input: &'a [u8]) -> RawReader<'a> {
+        RawReader::with_options(input, Options::default())
+    }
+
+    /// Create a raw reader over an input with options.
+    pub fn with_options(input: &'a [u8], options: Options) -> RawReader<'a> {
+        RawReader {
+            input,
+            pos: skip_rows(input, options.skip_rows),
+            delimiter: options.delimiter,
+            quotes: options.quotes,
+            projection: options.projection,
+            num_expected_fields: None,
+        }
+    }
+
+    /// Read the next record, writing the span of each field into `fields`.
+    ///
+    /// Returns the number of fields in the record, or `None` at the end of the
+    /// input. Fails if `fields` is too small to hold every field. If a
+    /// projection is configured, only the selected fields are written.
+    pub fn read_record(&mut self, fields: &mut [Span]) -> Result<Option<usize>> {
+        if self.pos == self.input.len() {
+            return Ok(None);
+        }
+        if let Some(projection) = &self.projection {
+            if fields.len() < projection.len() {
+                return Err(Error::BufferTooSmall);
+            }
+        }
+
+        let mut len = 0;
+        // an empty line is a record without fields
+        if let Some(newline) = self.scan_newline() {
+            self.pos += newline;
+        } else {
+            loop {
+                let span = self.field()?;
+                match &self.projection {
+                    Some(projection) => {
+                        for (ix, _) in projection.iter().enumerate().filter(|(_, &c)| c == len) {
+                            fields[ix] = span;
+                        }
+                    }
+                    None => *fields.get_mut(len).ok_or(Error::BufferTooSmall)? = span,
+                }
+                len += 1;
+                if self.pos == self.input.len() {
+                    break;
+                }
+                if let Some(newline) = self.scan_newline() {
+                    self.pos += newline;
+                    break;
+                }
+                if self.input[self.pos] == self.delimiter {
+                    self.pos += 1;
+                    continue;
+                }
+                // disallow consecutive values
+                return Err(Error::Parse);
+            }
+        }
+
+        match self.num_expected_fields {
+            None => self.num_expected_fields = Some(len),
+            Some(expected) if len != expected => return Err(Error::Parse),
+            _ => {}
+        }
+
+        match &self.projection {
+            Some(projection) if projection.iter().any(|&c| c >= len) => Err(Error::OutOfBounds),
+            Some(projection) => Ok(Some(projection.len())),
+            None => Ok(Some(len)),
+        }
+    }
+
+    /// Scan one field, leaving the position at the following delimiter,
+    /// newline, or end of input.
+    fn field(&mut self) -> Result<Span> {
+        let bytes = self.input;
+        if self.quotes == Quotes::Significant && bytes.get(self.pos) == Some(&b'"') {
+            let start = self.pos + 1;
+            let mut ix = start;
+            let mut escaped = false;
+            loop {
+                match bytes.get(ix) {
+                    // disallow unterminated quotes
+                    None => return Err(Error::Parse),
+                    Some(b'"') if bytes.get(ix + 1) == Some(&b'"') => {
+                        escaped = true;
+                        ix += 2;
+                    }
+                    Some(b'"') => break,
+                    Some(_) => ix += 1,
+                }
+            }
+            self.pos = ix + 1;
+            return Ok(Span {
+                start,
+                end: ix,
+                escaped,
+            });
+        }
+
+        let start = self.pos;
+        while self.pos < bytes.len()
+            && bytes[self.pos] != self.delimiter
+            && self.scan_newline().is_none()
+        {
+            self.pos += 1;
+        }
+        Ok(Span {
+            start,
+            end: self.pos,
+            escaped: false,
+        })
+    }
+
+    fn scan_newline(&self) -> Option<usize> {
+        match &self.input[self.pos..] {
+            [b'\n', ..] => Some(1),
+            [b'\r', b'\n', ..] => Some(2),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dsv;
+
+    fn read_all(text: &str) -> Result<Vec<Vec<String>>> {
+        let input = text.as_bytes();
+        let mut reader = RawReader::new(input);
+        let mut fields = [Span::default(); 8];
+        let mut records = vec![];
+        while let Some(len) = reader.read_record(&mut fields)? {
+            let record = fields[..len]
+                .iter()
+                .map(|span| String::from_utf8(span.unescape(input).collect()).unwrap())
+                .collect();
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    #[test]
+    fn matches_parser() -> Result<()> {
+        let inputs = [
+            "foo",
+            "foo,bar\r\nbaz,bux\n",
+            ",foo",
+            "foo,",
+            ",,",
+            "\"foo\nbar\",\"ba\"\"z\"",
+            "\"\"\"\",x",
+            "a\"b,c",
+        ];
+        for input in inputs {
+            let expected = Dsv::from_str(input)?;
+            assert_eq!(read_all(input)?, expected.records(), "{input:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn spans() -> Result<()> {
+        let input = b"foo,\"b\"\"ar\"";
+        let mut fields = [Span::default(); 2];
+        let len = RawReader::new(input).read_record(&mut fields)?;
+        assert_eq!(len, Some(2));
+        assert_eq!(fields[0].bytes(input), b"foo");
+        assert_eq!(fields[1].bytes(input), b"b\"\"ar");
+        assert!(fields[1].escaped);
+        Ok(())
+    }
+
+    #[test]
+    fn projection() -> Result<()> {
+        let input = b"foo,bar,baz";
+        let options = Options {
+            projection: Some(vec![2, 0]),
+            ..Default::default()
+        };
+        let mut fields = [Span::default(); 2];
+        let len = RawReader::with_options(input, options).read_record(&mut fields)?;
+        assert_eq!(len, Some(2));
+        assert_eq!(fields[0].bytes(input), b"baz");
+        assert_eq!(fields[1].bytes(input), b"foo");
+        Ok(())
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let mut fields = [Span::default(); 1];
+        let result = RawReader::new(b"foo,bar").read_record(&mut fields);
+        assert!(matches!(result, Err(Error::BufferTooSmall)));
+    }
+
+    #[test]
+    fn consecutive_values() {
+        let mut fields = [Span::default(); 2];
+        let result = RawReader::new(b"\"foo\"bar").read_record(&mut fields);
+        assert!(matches!(result, Err(Error::Parse)));
+    }
+}
@@ -0,0 +1,176 @@
+use alloc::{borrow::Cow, vec::Vec};
+
+#[cfg(feature = "std")]
+use crate::mmap::Mmap;
+use crate::{
+    error::{Error, Result},
+    index::RecordIndex,
+    parser::Parser,
+    Options,
+};
+
+/// A lazy reader over delimiter-separated values.
+///
+/// A `Reader` parses one record at a time from text that is already in memory,
+/// such as a string or a memory-mapped file. Fields borrow from the input
+/// where possible and are only copied when unescaping is required.
+pub struct Reader<'a> {
+    parser: Parser<'a>,
+    done: bool,
+}
+
+impl<'a> Reader<'a> {
+    /// Create a reader over an input string.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &'a str) -> Reader<'a> {
+        Reader::from_str_with_options(text, Options::default())
+    }
+
+    /// Create a reader over an input string with options.
+    pub fn from_str_with_options(text: &'a str, options: Options) -> Reader<'a> {
+        Reader {
+            parser: Parser::new(text, options),
+            done: false,
+        }
+    }
+
+    /// Create a reader over a memory-mapped file.
+    #[cfg(feature = "std")]
+    pub fn from_mmap(mmap: &'a Mmap) -> Reader<'a> {
+        Reader::from_str(mmap.as_str())
+    }
+
+    /// Create a reader over a memory-mapped file with options.
+    #[cfg(feature = "std")]
+    pub fn from_mmap_with_options(mmap: &'a Mmap, options: Options) -> Reader<'a> {
+        Reader::from_str_with_options(mmap.as_str(), options)
+    }
+
+    /// Move to the specified record, using an index built from the same input.
+    ///
+    /// Records can be read from that point onwards. To read a range of records,
+    /// seek to the start of the range and take the number of records needed.
+    pub fn seek(&mut self, index: &RecordIndex, record: usize) -> Result<()> {
+        index.check(self.parser.text())?;
+        let offset = match index.offset(record) {
+            Some(offset) => offset,
+            None if record == index.len() => self.parser.text().len(),
+            None => return Err(Error::OutOfBounds),
+        };
+        if !self.parser.text().is_char_boundary(offset) {
+            return Err(Error::IndexMismatch);
+        }
+        self.parser.seek(offset);
+        self.done = false;
+        Ok(())
+    }
+
+    /// Returns the next record in the input, with `None` in place of fields
+    /// that match one of `Options::null_values`.
+    ///
+    /// Like the iterator, the reader stops after the first error.
+    pub fn next_record_opt(&mut self) -> Option<Result<Vec<Option<Cow<'a, str>>>>> {
+        self.advance(Parser::next_record_opt)
+    }
+
+    /// Parse the next record, stopping at the end of the input or the first
+    /// error.
+    fn advance<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Parser<'a>) -> Result<Option<T>>,
+    ) -> Option<Result<T>> {
+        if self.done {
+            return None;
+        }
+        match parse(&mut self.parser) {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = Result<Vec<Cow<'a, str>>>;
+
+    /// Returns the next record in the input.
+    ///
+    /// The reader stops after the first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance(Parser::next_record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrowed_fields() {
+        let text = "foo,\"bar\"\nbaz,\"b\"\"ux\"";
+        let mut reader = Reader::from_str(text);
+        let record = reader.next().unwrap().unwrap();
+        assert!(matches!(record[0], Cow::Borrowed("foo")));
+        assert!(matches!(record[1], Cow::Borrowed("bar")));
+        let record = reader.next().unwrap().unwrap();
+        assert!(matches!(record[0], Cow::Borrowed("baz")));
+        assert!(matches!(record[1], Cow::Owned(ref value) if value == "b\"ux"));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn stops_after_error() {
+        let text = "foo,bar\nbaz\nqux,quux";
+        let mut reader = Reader::from_str(text);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn seek() -> Result<()> {
+        let text = "a,1\n\"b\nb\",2\nc,3\nd,4";
+        let index = RecordIndex::from_str(text)?;
+        let mut reader = Reader::from_str(text);
+        reader.seek(&index, 1)?;
+        let records: Vec<_> = reader.by_ref().take(2).collect::<Result<_>>()?;
+        assert_eq!(records, vec![vec!["b\nb", "2"], vec!["c", "3"]]);
+        reader.seek(&index, 0)?;
+        assert_eq!(reader.next().unwrap()?, vec!["a", "1"]);
+        assert!(matches!(reader.seek(&index, 5), Err(Error::OutOfBounds)));
+        Ok(())
+    }
+
+    #[test]
+    fn seek_stale_index() -> Result<()> {
+        let index = RecordIndex::from_str("a\nb")?;
+        let mut reader = Reader::from_str("a\nbc");
+        assert!(matches!(reader.seek(&index, 1), Err(Error::IndexMismatch)));
+
+        // an index of the same length whose offset splits a character
+        let index = RecordIndex::from_str("a\nbc")?;
+        let mut reader = Reader::from_str("aé\n");
+        assert!(matches!(reader.seek(&index, 1), Err(Error::IndexMismatch)));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn mmap() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("dsv-mmap-{}.csv", std::process::id()));
+        std::fs::write(&path, "foo,bar\nbaz,bux\n")?;
+        // SAFETY: the file is private to this test and is not modified while
+        // it is mapped.
+        let mmap = unsafe { Mmap::open(&path)? };
+        let records: Vec<_> = Reader::from_mmap(&mmap).collect::<Result<_>>()?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(records, vec![vec!["foo", "bar"], vec!["baz", "bux"]]);
+        Ok(())
+    }
+}
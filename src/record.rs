@@ -0,0 +1,322 @@
+use alloc::{string::String, vec::Vec};
+use core::{ops::Range, str::FromStr};
+
+use crate::{
+    column::Column,
+    convert::NumberFormat,
+    datetime::{DateTime, Format},
+    error::{Error, Result},
+    Dsv,
+};
+
+/// A view of one record in a table, with access to fields by column name.
+///
+/// Created by `Dsv::record` and `Dsv::iter`.
+#[derive(Clone, Copy, Debug)]
+pub struct Record<'a> {
+    dsv: &'a Dsv,
+    index: usize,
+}
+
+impl<'a> Record<'a> {
+    /// Get the index of this record in the table.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the fields in this record.
+    pub fn fields(&self) -> &'a [String] {
+        &self.dsv.records[self.index]
+    }
+
+    /// Get the field in the specified column.
+    ///
+    /// Returns `None` if there is no such column.
+    pub fn get<'c, C: Into<Column<'c>>>(&self, column: C) -> Option<&'a str> {
+        let column = self.dsv.column_index(column).ok()?;
+        Some(&self.fields()[column])
+    }
+
+    /// Get the field in the specified column, or `None` if it is null.
+    ///
+    /// Fails if there is no such column.
+    pub fn get_opt<'c, C: Into<Column<'c>>>(&self, column: C) -> Result<Option<&'a str>> {
+        let column = self.dsv.column_index(column)?;
+        Ok(self.field_opt(column))
+    }
+
+    /// Get the fields in this record, with `None` in place of null fields.
+    pub fn fields_opt(&self) -> Vec<Option<&'a str>> {
+        (0..self.fields().len())
+            .map(|column| self.field_opt(column))
+            .collect()
+    }
+
+    fn field_opt(&self, column: usize) -> Option<&'a str> {
+        self.dsv.field_opt(self.index, column)
+    }
+
+    /// Parse the field in the specified column.
+    ///
+    /// Fails with `Error::Value` if the field can not be parsed.
+    pub fn get_as<'c, T: FromStr>(&self, column: impl Into<Column<'c>>) -> Result<T> {
+        let column = self.dsv.column_index(column)?;
+        parse(self.fields(), self.index, column)
+    }
+
+    /// Parse the field in the specified column as a date and time with a
+    /// format. See `DateTime::parse_with_format` for the syntax.
+    ///
+    /// Fails with `Error::Value` if the field does not match the format.
+    pub fn get_datetime<'c, C: Into<Column<'c>>>(
+        &self,
+        column: C,
+        format: &str,
+    ) -> Result<DateTime> {
+        let column = self.dsv.column_index(column)?;
+        let format = Format::new(format)?;
+        parse_with(self.fields(), self.index, column, |value| {
+            format.parse(value)
+        })
+    }
+
+    /// Parse the field in the specified column as a number in a format.
+    ///
+    /// Fails with `Error::Value` if the field is not a number in the format.
+    pub fn get_number<'c, C: Into<Column<'c>>>(
+        &self,
+        column: C,
+        format: &NumberFormat,
+    ) -> Result<f64> {
+        let column = self.dsv.column_index(column)?;
+        parse_with(self.fields(), self.index, column, |value| {
+            format.parse_f64(value).ok()
+        })
+    }
+}
+
+/// Parse the field in a column of a record, reporting its location on failure.
+pub(crate) fn parse<T: FromStr>(record: &[String], index: usize, column: usize) -> Result<T> {
+    parse_with(record, index, column, |value| value.parse().ok())
+}
+
+fn parse_with<T, F>(record: &[String], index: usize, column: usize, parse: F) -> Result<T>
+where
+    F: FnOnce(&str) -> Option<T>,
+{
+    let value = &record[column];
+    parse(value).ok_or_else(|| Error::Value {
+        record: index,
+        column,
+        value: value.clone(),
+    })
+}
+
+/// An iterator over the records of a table.
+///
+/// Created by `Dsv::iter`.
+pub struct Records<'a> {
+    dsv: &'a Dsv,
+    indexes: Range<usize>,
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.indexes.next()?;
+        Some(Record {
+            dsv: self.dsv,
+            index,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indexes.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Records<'_> {}
+
+impl Dsv {
+    /// Get the record at the specified index.
+    ///
+    /// Returns `None` if there is no record at the specified index.
+    pub fn record(&self, index: usize) -> Option<Record<'_>> {
+        if index >= self.records.len() {
+            return None;
+        }
+        Some(Record { dsv: self, index })
+    }
+
+    /// Iterate over the records in this table.
+    pub fn iter(&self) -> Records<'_> {
+        Records {
+            dsv: self,
+            indexes: 0..self.records.len(),
+        }
+    }
+
+    /// Parse every field in a column.
+    ///
+    /// Fails with `Error::Value` at the first field that can not be parsed.
+    pub fn parse_column<'c, T: FromStr>(&self, column: impl Into<Column<'c>>) -> Result<Vec<T>> {
+        let column = self.column_index(column)?;
+        self.records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| parse(record, index, column))
+            .collect()
+    }
+
+    /// Parse every field in a column as a date and time with a format.
+    ///
+    /// Use `DateTime::detect_format` to find a format that every field in the
+    /// column matches. Fails with `Error::Value` at the first field that does
+    /// not match the format.
+    pub fn parse_datetime_column<'c, C: Into<Column<'c>>>(
+        &self,
+        column: C,
+        format: &str,
+    ) -> Result<Vec<DateTime>> {
+        let column = self.column_index(column)?;
+        let format = Format::new(format)?;
+        self.records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| parse_with(record, index, column, |v| format.parse(v)))
+            .collect()
+    }
+
+    /// Parse every field in a column as a number in a format.
+    ///
+    /// Fails with `Error::Value` at the first field that is not a number in
+    /// the format.
+    pub fn parse_number_column<'c, C: Into<Column<'c>>>(
+        &self,
+        column: C,
+        format: &NumberFormat,
+    ) -> Result<Vec<f64>> {
+        let column = self.column_index(column)?;
+        self.records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| parse_with(record, index, column, |v| format.parse_f64(v).ok()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsvBuilder, Options};
+
+    fn fruits() -> Dsv {
+        DsvBuilder::new()
+            .headers(["fruit", "count"])
+            .record(["apples", "2"])
+            .record(["oranges", "three"])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn get() {
+        let dsv = fruits();
+        let record = dsv.record(1).unwrap();
+        assert_eq!(record.index(), 1);
+        assert_eq!(record.get("fruit"), Some("oranges"));
+        assert_eq!(record.get(1), Some("three"));
+        assert_eq!(record.get("price"), None);
+        assert!(dsv.record(2).is_none());
+    }
+
+    #[test]
+    fn get_as() -> Result<()> {
+        let dsv = fruits();
+        assert_eq!(dsv.record(0).unwrap().get_as::<u32>("count")?, 2);
+        let result = dsv.record(1).unwrap().get_as::<u32>("count");
+        assert!(matches!(
+            result,
+            Err(Error::Value { record: 1, column: 1, value }) if value == "three"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn get_opt() -> Result<()> {
+        let options = Options {
+            headers: true,
+            null_values: vec!["".into(), "\\N".into()],
+            ..Default::default()
+        };
+        let dsv = Dsv::from_str_with_options("fruit,count\napples,\\N\n\"\",", options)?;
+        let record = dsv.record(0).unwrap();
+        assert_eq!(record.get_opt("fruit")?, Some("apples"));
+        assert_eq!(record.get_opt("count")?, None);
+        assert_eq!(record.get("count"), Some(""));
+        assert!(matches!(
+            record.get_opt("price"),
+            Err(Error::UnknownColumn(_))
+        ));
+        assert_eq!(dsv.record(1).unwrap().fields_opt(), [Some(""), None]);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_column() -> Result<()> {
+        let dsv = Dsv::from_str("1,a\n2,b")?;
+        assert_eq!(dsv.parse_column::<i64>(0)?, [1, 2]);
+        assert!(matches!(
+            fruits().parse_column::<i64>("count"),
+            Err(Error::Value { record: 1, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn datetimes() -> Result<()> {
+        let dsv = Dsv::from_str("18/07/2023 14:05,x\n01/08/2023 09:30,y")?;
+        let record = dsv.record(0).unwrap();
+        let dt = record.get_datetime(0, "%d/%m/%Y %H:%M")?;
+        assert_eq!((dt.year, dt.month, dt.day, dt.hour), (2023, 7, 18, 14));
+        assert!(matches!(
+            record.get_datetime(1, "%d/%m/%Y"),
+            Err(Error::Value { column: 1, .. })
+        ));
+        assert!(matches!(
+            record.get_datetime(0, "%Q"),
+            Err(Error::Expression(_))
+        ));
+
+        let format = DateTime::detect_format(dsv.column(0).unwrap()).unwrap();
+        let dates = dsv.parse_datetime_column(0, format)?;
+        assert_eq!(dates[1].to_string(), "2023-08-01T09:30:00");
+        Ok(())
+    }
+
+    #[test]
+    fn numbers() -> Result<()> {
+        let dsv = Dsv::from_str("\"1.234,5 €\",x\n12%,y")?;
+        let format = NumberFormat::new()
+            .decimal(',')
+            .grouping('.')
+            .currency("€")
+            .percent("%");
+        assert_eq!(dsv.record(0).unwrap().get_number(0, &format)?, 1234.5);
+        assert!(matches!(
+            dsv.record(0).unwrap().get_number(1, &format),
+            Err(Error::Value { column: 1, .. })
+        ));
+        assert_eq!(dsv.parse_number_column(0, &format)?, [1234.5, 0.12]);
+        Ok(())
+    }
+
+    #[test]
+    fn iter() {
+        let dsv = fruits();
+        let fruits: Vec<_> = dsv.iter().filter_map(|r| r.get("fruit")).collect();
+        assert_eq!(fruits, ["apples", "oranges"]);
+        assert_eq!(dsv.iter().len(), 2);
+    }
+}
@@ -0,0 +1,242 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    column::Column,
+    error::{Error, Result},
+    Dsv,
+};
+
+impl Dsv {
+    /// Swap the records and columns of the table.
+    ///
+    /// If the table has headers, they become the first column of the result,
+    /// which does not have headers.
+    pub fn transpose(&self) -> Dsv {
+        let records = (0..self.width())
+            .map(|column| {
+                let header = self
+                    .headers
+                    .iter()
+                    .map(|headers| Some(headers[column].clone()));
+                let fields = (0..self.records.len())
+                    .map(|record| self.field_opt(record, column).map(String::from));
+                header.chain(fields).collect()
+            })
+            .collect();
+        Dsv::from_opt(None, records)
+    }
+
+    /// Reshape a long table into a wide one.
+    ///
+    /// The result contains a record for each distinct value of the `index`
+    /// column and a column for each distinct value of the `columns` column,
+    /// both in order of first appearance. Each field is taken from the `values`
+    /// column of the matching record, or left empty if there is none, and is
+    /// null if that field is null. The result has headers, made up of the
+    /// header of the `index` column followed by the values of the `columns`
+    /// column.
+    ///
+    /// Fails with `Error::Duplicate` if more than one record has the same
+    /// index and column.
+    pub fn pivot<'a, 'b, 'c, I, C, V>(&self, index: I, columns: C, values: V) -> Result<Dsv>
+    where
+        I: Into<Column<'a>>,
+        C: Into<Column<'b>>,
+        V: Into<Column<'c>>,
+    {
+        let index = self.column_index(index)?;
+        let columns = self.column_index(columns)?;
+        let values = self.column_index(values)?;
+
+        let mut rows = BTreeMap::new();
+        let mut cols = BTreeMap::new();
+        let mut filled = BTreeSet::new();
+        let mut headers = vec![self.header(index)];
+        let mut records: Vec<Vec<Option<String>>> = vec![];
+        for (ix, record) in self.records.iter().enumerate() {
+            let next_col = cols.len() + 1;
+            let col = *cols.entry(self.field_opt(ix, columns)).or_insert_with(|| {
+                headers.push(record[columns].clone());
+                for record in &mut records {
+                    record.push(Some(String::new()));
+                }
+                next_col
+            });
+            let row = *rows.entry(self.field_opt(ix, index)).or_insert_with(|| {
+                let mut row = vec![Some(String::new()); headers.len()];
+                row[0] = self.field_opt(ix, index).map(String::from);
+                records.push(row);
+                records.len() - 1
+            });
+
+            if !filled.insert((row, col)) {
+                return Err(Error::Duplicate { record: ix });
+            }
+            records[row][col] = self.field_opt(ix, values).map(String::from);
+        }
+
+        Ok(Dsv::from_opt(Some(headers), records))
+    }
+
+    /// Reshape a wide table into a long one.
+    ///
+    /// The result contains a record for each pair of input record and value
+    /// column, made up of the fields in the id columns, the name of the value
+    /// column, and its field. If no value columns are specified, every column
+    /// that is not an id column is used. The result has headers, named after
+    /// the id columns followed by `variable` and `value`.
+    pub fn melt<'a, 'b, I, V>(&self, id_columns: &[I], value_columns: &[V]) -> Result<Dsv>
+    where
+        I: Copy + Into<Column<'a>>,
+        V: Copy + Into<Column<'b>>,
+    {
+        let ids = self.resolve_columns(id_columns)?;
+        let mut values = self.resolve_columns(value_columns)?;
+        if values.is_empty() {
+            values = (0..self.width()).filter(|c| !ids.contains(c)).collect();
+        }
+
+        let mut headers: Vec<String> = ids.iter().map(|&c| self.header(c)).collect();
+        headers.push("variable".into());
+        headers.push("value".into());
+
+        let mut records = vec![];
+        for record in 0..self.records.len() {
+            for &column in &values {
+                let field = |column| self.field_opt(record, column).map(String::from);
+                let mut melted: Vec<_> = ids.iter().map(|&c| field(c)).collect();
+                melted.push(Some(self.header(column)));
+                melted.push(field(column));
+                records.push(melted);
+            }
+        }
+
+        Ok(Dsv::from_opt(Some(headers), records))
+    }
+
+    /// Get the name of a column, which is its index if there are no headers.
+    fn header(&self, column: usize) -> String {
+        match &self.headers {
+            Some(headers) => headers[column].clone(),
+            None => column.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsvBuilder, Options};
+
+    fn long() -> Dsv {
+        DsvBuilder::new()
+            .headers(["city", "date", "temp"])
+            .record(["Berlin", "mon", "10"])
+            .record(["Berlin", "tue", "12"])
+            .record(["Paris", "tue", "15"])
+            .record(["Paris", "mon", "14"])
+            .record(["Rome", "wed", "20"])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn transpose() -> Result<()> {
+        let dsv = DsvBuilder::new()
+            .headers(["a", "b"])
+            .record(["1", "2"])
+            .record(["3", "4"])
+            .build()?;
+        let transposed = dsv.transpose();
+        assert_eq!(transposed.headers(), None);
+        assert_eq!(transposed.records(), [["a", "1", "3"], ["b", "2", "4"]]);
+        assert_eq!(transposed.transpose().records()[1], ["1", "2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn pivot() -> Result<()> {
+        let wide = long().pivot("city", "date", "temp")?;
+        assert_eq!(wide.headers().unwrap(), ["city", "mon", "tue", "wed"]);
+        assert_eq!(
+            wide.records(),
+            [
+                ["Berlin", "10", "12", ""],
+                ["Paris", "14", "15", ""],
+                ["Rome", "", "", "20"],
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pivot_duplicate() {
+        let mut dsv = long();
+        dsv.push_record(vec!["Paris".into(), "mon".into(), "".into()])
+            .unwrap();
+        let result = dsv.pivot(0, 1, 2);
+        assert!(matches!(result, Err(Error::Duplicate { record: 5 })));
+
+        let dsv = Dsv::from_str("a,x,\na,x,").unwrap();
+        let result = dsv.pivot(0, 1, 2);
+        assert!(matches!(result, Err(Error::Duplicate { record: 1 })));
+    }
+
+    #[test]
+    fn melt() -> Result<()> {
+        let wide = long().pivot("city", "date", "temp")?;
+        let melted = wide.melt(&["city"], &["mon", "wed"])?;
+        assert_eq!(melted.headers().unwrap(), ["city", "variable", "value"]);
+        assert_eq!(melted.records().len(), 6);
+        assert_eq!(melted[0], ["Berlin", "mon", "10"]);
+        assert_eq!(melted[5], ["Rome", "wed", "20"]);
+
+        let all = wide.melt(&["city"], &[] as &[usize])?;
+        assert_eq!(all.records().len(), 9);
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let read = |text, headers| {
+            let options = Options {
+                headers,
+                null_values: vec!["NA".into()],
+                ..Default::default()
+            };
+            Dsv::from_str_with_options(text, options)
+        };
+        let dsv = read("1,NA\n3,4", false)?;
+        assert_eq!(dsv.transpose(), read("1,3\nNA,4", false)?);
+
+        let long = read(
+            "city,date,temp\nBerlin,mon,NA\nNA,mon,5\nBerlin,tue,6",
+            true,
+        )?;
+        let wide = long.pivot("city", "date", "temp")?;
+        assert_eq!(wide, read("city,mon,tue\nBerlin,NA,6\nNA,5,", true)?);
+        let split = read("k,c,v\na,NA,1\na,,2", true)?.pivot("k", "c", "v")?;
+        assert_eq!(split.headers().unwrap(), ["k", "", ""]);
+        assert_eq!(split.records(), [["a", "1", "2"]]);
+
+        let melted = wide.melt(&["city"], &["mon"])?;
+        assert_eq!(
+            melted,
+            read("city,variable,value\nBerlin,mon,NA\nNA,mon,5", true)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn melt_without_headers() -> Result<()> {
+        let dsv = Dsv::from_str("a,1,2")?;
+        let melted = dsv.melt(&[0], &[1, 2])?;
+        assert_eq!(melted.records(), [["a", "1", "1"], ["a", "2", "2"]]);
+        Ok(())
+    }
+}
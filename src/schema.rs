@@ -0,0 +1,550 @@
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{
+    convert::NumberFormat,
+    error::{Error, Result},
+    infer::{DataType, InferredColumn},
+    Dsv,
+};
+
+/// A declared column of a `Schema` and the constraints on its values.
+///
+/// Empty values are only checked by `required`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Field {
+    pub name: String,
+    pub data_type: DataType,
+    /// Whether values must not be empty.
+    pub required: bool,
+    /// A regular expression that values must match in their entirety.
+    pub pattern: Option<String>,
+    /// The values that are allowed, if restricted.
+    pub values: Option<Vec<String>>,
+    /// The smallest allowed value, for numeric fields.
+    pub minimum: Option<f64>,
+    /// The largest allowed value, for numeric fields.
+    pub maximum: Option<f64>,
+    /// The format of numeric values.
+    pub number_format: NumberFormat,
+}
+
+impl Field {
+    /// Declare an optional field of the specified type.
+    pub fn new<S: Into<String>>(name: S, data_type: DataType) -> Field {
+        Field {
+            name: name.into(),
+            data_type,
+            required: false,
+            pattern: None,
+            values: None,
+            minimum: None,
+            maximum: None,
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// Require values to be non-empty.
+    pub fn required(mut self) -> Field {
+        self.required = true;
+        self
+    }
+
+    /// Require values to match a regular expression, which requires the
+    /// `regex` feature.
+    pub fn pattern<S: Into<String>>(mut self, pattern: S) -> Field {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Restrict values to those specified.
+    pub fn one_of<I, T>(mut self, values: I) -> Field
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.values = Some(values.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Require numeric values to be at least `minimum`.
+    pub fn minimum(mut self, minimum: f64) -> Field {
+        self.minimum = Some(minimum);
+        self
+    }
+
+    /// Require numeric values to be at most `maximum`.
+    pub fn maximum(mut self, maximum: f64) -> Field {
+        self.maximum = Some(maximum);
+        self
+    }
+
+    /// Set the format of numeric values.
+    pub fn number_format(mut self, number_format: NumberFormat) -> Field {
+        self.number_format = number_format;
+        self
+    }
+}
+
+/// A declaration of the columns a table is expected to have.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Schema {
+    pub fields: Vec<Field>,
+    /// Sets of field names whose combined values must be unique.
+    pub unique_keys: Vec<Vec<String>>,
+}
+
+impl Schema {
+    /// Create a schema with the specified fields.
+    pub fn new(fields: Vec<Field>) -> Schema {
+        Schema {
+            fields,
+            unique_keys: vec![],
+        }
+    }
+
+    /// Require the combined values of the specified fields to be unique.
+    pub fn unique<I, T>(mut self, key: I) -> Schema
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.unique_keys
+            .push(key.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+/// The ways in which a table can violate a schema.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ViolationKind {
+    /// No column has the name of the field.
+    MissingColumn,
+    /// A required value is empty.
+    Required,
+    /// A value is not of the field's type.
+    Type(DataType),
+    /// A value does not match the field's pattern.
+    Pattern,
+    /// A value is not one of the field's allowed values.
+    NotAllowed,
+    /// A value is less than the field's minimum.
+    BelowMinimum,
+    /// A value is greater than the field's maximum.
+    AboveMaximum,
+    /// A unique key has the same values as the key of an earlier record.
+    Duplicate { first: usize },
+}
+
+/// A violation of a schema.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Violation {
+    /// The index of the record, or `None` for violations of the headers.
+    pub record: Option<usize>,
+    /// The index of the column, if the field has one.
+    pub column: Option<usize>,
+    /// The name of the field, or the names of the fields in a unique key
+    /// separated by commas.
+    pub field: String,
+    /// The offending value, or the values of a unique key separated by commas.
+    pub value: String,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(record) = self.record {
+            write!(f, "record {record}, ")?;
+        }
+        match self.column {
+            Some(column) => write!(f, "column {column} ({}): ", self.field)?,
+            None => write!(f, "{}: ", self.field)?,
+        }
+        match &self.kind {
+            ViolationKind::MissingColumn => write!(f, "column is missing"),
+            ViolationKind::Required => write!(f, "value is required"),
+            ViolationKind::Type(data_type) => {
+                write!(f, "{:?} is not of type {data_type:?}", self.value)
+            }
+            ViolationKind::Pattern => write!(f, "{:?} does not match the pattern", self.value),
+            ViolationKind::NotAllowed => write!(f, "{:?} is not an allowed value", self.value),
+            ViolationKind::BelowMinimum => write!(f, "{:?} is below the minimum", self.value),
+            ViolationKind::AboveMaximum => write!(f, "{:?} is above the maximum", self.value),
+            ViolationKind::Duplicate { first } => {
+                write!(f, "{:?} duplicates record {first}", self.value)
+            }
+        }
+    }
+}
+
+/// Checks records against a schema one at a time.
+///
+/// If `validate_headers` is called first, fields are matched to columns by
+/// name. Otherwise, they are matched by position.
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use dsv::{DataType, Field, Schema, StreamReader, Validator};
+///
+/// let schema = Schema::new(vec![Field::new("count", DataType::Integer)]);
+/// let mut validator = Validator::new(&schema).unwrap();
+/// let mut records = StreamReader::new("count\n2\nthree\n".as_bytes());
+/// let headers = records.next().unwrap().unwrap();
+/// let mut violations = validator.validate_headers(&headers);
+/// for record in records {
+///     violations.extend(validator.validate_record(&record.unwrap()));
+/// }
+///
+/// assert_eq!(violations.len(), 1);
+/// assert_eq!(violations[0].record, Some(1));
+/// # }
+/// ```
+pub struct Validator<'a> {
+    schema: &'a Schema,
+    columns: Vec<Option<usize>>,
+    #[cfg(feature = "regex")]
+    patterns: Vec<Option<regex::Regex>>,
+    keys: Vec<UniqueKey>,
+    record: usize,
+}
+
+/// The field indexes of a unique key, and the record in which each value of
+/// the key was first seen.
+struct UniqueKey {
+    fields: Vec<usize>,
+    seen: BTreeMap<Vec<String>, usize>,
+}
+
+impl<'a> Validator<'a> {
+    /// Create a validator for a schema.
+    ///
+    /// Fails if a pattern is not a valid regular expression, or if the schema
+    /// has patterns and the `regex` feature is disabled. Fails with
+    /// `Error::UnknownColumn` if a unique key refers to an undeclared field.
+    pub fn new(schema: &'a Schema) -> Result<Validator<'a>> {
+        #[cfg(feature = "regex")]
+        let patterns = schema
+            .fields
+            .iter()
+            .map(|field| {
+                field
+                    .pattern
+                    .as_ref()
+                    .map(|pattern| {
+                        regex::Regex::new(&alloc::format!("^(?:{pattern})$"))
+                            .map_err(|e| Error::Expression(alloc::format!("{e}")))
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        #[cfg(not(feature = "regex"))]
+        if schema.fields.iter().any(|field| field.pattern.is_some()) {
+            let message = "pattern matching requires the `regex` feature";
+            return Err(Error::Expression(message.into()));
+        }
+
+        let keys = schema
+            .unique_keys
+            .iter()
+            .map(|key| {
+                let fields = key
+                    .iter()
+                    .map(|name| {
+                        let field = schema.fields.iter().position(|f| &f.name == name);
+                        field.ok_or_else(|| Error::UnknownColumn(name.clone()))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(UniqueKey {
+                    fields,
+                    seen: BTreeMap::new(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Validator {
+            schema,
+            columns: (0..schema.fields.len()).map(Some).collect(),
+            #[cfg(feature = "regex")]
+            patterns,
+            keys,
+            record: 0,
+        })
+    }
+
+    /// Get the number of records validated so far.
+    pub fn records(&self) -> usize {
+        self.record
+    }
+
+    /// Match fields to columns by name, reporting fields without a column.
+    pub fn validate_headers<T: AsRef<str>>(&mut self, headers: &[T]) -> Vec<Violation> {
+        let mut violations = vec![];
+        for (ix, field) in self.schema.fields.iter().enumerate() {
+            self.columns[ix] = headers.iter().position(|h| h.as_ref() == field.name);
+            if self.columns[ix].is_none() {
+                violations.push(Violation {
+                    record: None,
+                    column: None,
+                    field: field.name.clone(),
+                    value: String::new(),
+                    kind: ViolationKind::MissingColumn,
+                });
+            }
+        }
+        violations
+    }
+
+    /// Check the next record.
+    pub fn validate_record<T: AsRef<str>>(&mut self, record: &[T]) -> Vec<Violation> {
+        let index = self.record;
+        self.record += 1;
+        let value =
+            |column: Option<usize>| column.map(|c| record.get(c).map_or("", |v| v.as_ref()));
+
+        let mut violations = vec![];
+        for (ix, field) in self.schema.fields.iter().enumerate() {
+            let column = self.columns[ix];
+            let Some(value) = value(column) else {
+                continue;
+            };
+            if let Some(kind) = self.check(ix, field, value) {
+                violations.push(Violation {
+                    record: Some(index),
+                    column,
+                    field: field.name.clone(),
+                    value: value.into(),
+                    kind,
+                });
+            }
+        }
+
+        for UniqueKey { fields, seen } in &mut self.keys {
+            let columns: Vec<Option<usize>> = fields.iter().map(|&f| self.columns[f]).collect();
+            if columns.iter().any(Option::is_none) {
+                continue;
+            }
+            let key: Vec<String> = columns.iter().map(|&c| value(c).unwrap().into()).collect();
+            if let Some(&first) = seen.get(&key) {
+                let names: Vec<&str> = fields
+                    .iter()
+                    .map(|&f| self.schema.fields[f].name.as_str())
+                    .collect();
+                violations.push(Violation {
+                    record: Some(index),
+                    column: None,
+                    field: names.join(","),
+                    value: key.join(","),
+                    kind: ViolationKind::Duplicate { first },
+                });
+            } else {
+                seen.insert(key, index);
+            }
+        }
+
+        violations
+    }
+
+    /// Check a value against the constraints of a field.
+    fn check(&self, ix: usize, field: &Field, value: &str) -> Option<ViolationKind> {
+        if value.is_empty() {
+            return field.required.then_some(ViolationKind::Required);
+        }
+        let number_format = &field.number_format;
+        if !field.data_type.matches_with(value, number_format) {
+            return Some(ViolationKind::Type(field.data_type));
+        }
+        #[cfg(feature = "regex")]
+        if let Some(pattern) = &self.patterns[ix] {
+            if !pattern.is_match(value) {
+                return Some(ViolationKind::Pattern);
+            }
+        }
+        #[cfg(not(feature = "regex"))]
+        let _ = ix;
+        if let Some(values) = &field.values {
+            if !values.iter().any(|v| v == value) {
+                return Some(ViolationKind::NotAllowed);
+            }
+        }
+        if let Ok(number) = number_format.parse_f64(value) {
+            if field.minimum.is_some_and(|min| number < min) {
+                return Some(ViolationKind::BelowMinimum);
+            }
+            if field.maximum.is_some_and(|max| number > max) {
+                return Some(ViolationKind::AboveMaximum);
+            }
+        }
+        None
+    }
+}
+
+impl Dsv {
+    /// Check every record against a schema, returning the violations found.
+    ///
+    /// If the table has headers, fields are matched to columns by name.
+    /// Otherwise, they are matched by position. Fails if the schema is not
+    /// valid, as with `Validator::new`.
+    pub fn validate(&self, schema: &Schema) -> Result<Vec<Violation>> {
+        let mut validator = Validator::new(schema)?;
+        let mut violations = match self.headers() {
+            Some(headers) => validator.validate_headers(headers),
+            None => vec![],
+        };
+        for record in &self.records {
+            violations.extend(validator.validate_record(record));
+        }
+        Ok(violations)
+    }
+}
+
+impl From<&[InferredColumn]> for Schema {
+    /// Create a schema from inferred column types, requiring values in the
+    /// columns that are not nullable.
+    fn from(columns: &[InferredColumn]) -> Schema {
+        let fields = columns
+            .iter()
+            .map(|column| Field {
+                required: !column.nullable,
+                ..Field::new(column.name.to_string(), column.data_type)
+            })
+            .collect();
+        Schema::new(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DsvBuilder;
+
+    fn products() -> Dsv {
+        DsvBuilder::new()
+            .headers(["id", "name", "price", "country"])
+            .record(["1", "Apple", "1.5", "DE"])
+            .record(["2", "", "-1", "FR"])
+            .record(["x", "Cherry", "200", "US"])
+            .record(["1", "durian", "3", "DE"])
+            .build()
+            .unwrap()
+    }
+
+    fn schema() -> Schema {
+        let mut name = Field::new("name", DataType::String).required();
+        if cfg!(feature = "regex") {
+            name = name.pattern("[A-Z][a-z]+");
+        }
+        Schema::new(vec![
+            Field::new("id", DataType::Integer),
+            name,
+            Field::new("price", DataType::Float)
+                .minimum(0.0)
+                .maximum(100.0),
+            Field::new("country", DataType::String).one_of(["DE", "FR"]),
+        ])
+        .unique(["id"])
+    }
+
+    fn kinds(violations: &[Violation]) -> Vec<(Option<usize>, ViolationKind)> {
+        violations
+            .iter()
+            .map(|v| (v.record, v.kind.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn validate() -> Result<()> {
+        let violations = products().validate(&schema())?;
+        let mut expected = vec![
+            (Some(1), ViolationKind::Required),
+            (Some(1), ViolationKind::BelowMinimum),
+            (Some(2), ViolationKind::Type(DataType::Integer)),
+            (Some(2), ViolationKind::AboveMaximum),
+            (Some(2), ViolationKind::NotAllowed),
+        ];
+        if cfg!(feature = "regex") {
+            expected.push((Some(3), ViolationKind::Pattern));
+        }
+        expected.push((Some(3), ViolationKind::Duplicate { first: 0 }));
+        assert_eq!(kinds(&violations), expected);
+        assert_eq!(violations[0].column, Some(1));
+        assert_eq!(violations[2].value, "x");
+        Ok(())
+    }
+
+    #[test]
+    fn missing_column() -> Result<()> {
+        let dsv = products().select(&[0, 1])?;
+        let violations = dsv.validate(&Schema::new(vec![
+            Field::new("name", DataType::String),
+            Field::new("price", DataType::Float).required(),
+        ]))?;
+        assert_eq!(kinds(&violations), [(None, ViolationKind::MissingColumn)]);
+        assert_eq!(violations[0].field, "price");
+        Ok(())
+    }
+
+    #[test]
+    fn by_position() -> Result<()> {
+        let dsv = Dsv::from_str("1,a\nb,c")?;
+        let schema = Schema::new(vec![Field::new("id", DataType::Integer)]);
+        let violations = dsv.validate(&schema)?;
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "record 1, column 0 (id): \"b\" is not of type Integer"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn number_format() -> Result<()> {
+        let dsv = Dsv::from_str("\"1.234,5\",\"1,234.5\"\n\"12,5\",\"12.5\"")?;
+        let field = |name| Field::new(name, DataType::Float).maximum(100.0);
+        let schema = Schema::new(vec![field("price"), field("total")]);
+        assert_eq!(dsv.validate(&schema)?.len(), 3);
+        let schema = Schema::new(vec![
+            field("price").number_format(NumberFormat::new().decimal(',').grouping('.')),
+            field("total").number_format(NumberFormat::new().grouping(',')),
+        ]);
+        let violations = dsv.validate(&schema)?;
+        assert_eq!(
+            kinds(&violations),
+            [
+                (Some(0), ViolationKind::AboveMaximum),
+                (Some(0), ViolationKind::AboveMaximum)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_inferred() -> Result<()> {
+        let dsv = Dsv::from_str("1,a\n2,")?;
+        let schema = Schema::from(dsv.infer_schema().as_slice());
+        assert!(schema.fields[0].required);
+        assert!(!schema.fields[1].required);
+        assert!(dsv.validate(&schema)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_unique_key() {
+        let schema = schema().unique(["id", "sku"]);
+        assert!(matches!(
+            Validator::new(&schema),
+            Err(Error::UnknownColumn(name)) if name == "sku"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn invalid_pattern() {
+        let schema = Schema::new(vec![Field::new("a", DataType::String).pattern("(")]);
+        assert!(matches!(Validator::new(&schema), Err(Error::Expression(_))));
+    }
+}
@@ -0,0 +1,546 @@
+use alloc::{string::String, vec::Vec};
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+};
+
+#[cfg(feature = "std")]
+use crate::Options;
+use crate::{column::Column, error::Result, Dsv};
+#[cfg(feature = "std")]
+use crate::{StreamReader, Writer};
+
+/// The direction in which to sort a column.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// How fields are compared when sorting.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Compare {
+    /// Compare fields byte by byte.
+    #[default]
+    Lexical,
+    /// Compare fields as floating-point numbers. Fields that are not numbers
+    /// sort after those that are, and are compared lexically.
+    Numeric,
+    /// Compare runs of digits by their numeric value and everything else
+    /// lexically, so that `file2` sorts before `file10`.
+    Natural,
+    /// Compare fields lexically, ignoring case.
+    CaseInsensitive,
+}
+
+impl Compare {
+    /// Compare two fields.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Compare::Lexical => a.cmp(b),
+            Compare::Numeric => compare_numeric(a, b),
+            Compare::Natural => compare_natural(a, b),
+            Compare::CaseInsensitive => {
+                let a = a.chars().flat_map(char::to_lowercase);
+                let b = b.chars().flat_map(char::to_lowercase);
+                a.cmp(b)
+            }
+        }
+    }
+}
+
+fn compare_numeric(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+fn compare_natural(mut a: &str, mut b: &str) -> Ordering {
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+        let is_digits = x.is_ascii_digit();
+        if is_digits != y.is_ascii_digit() {
+            return a.cmp(b);
+        }
+
+        let split = |s: &str| s.find(|c: char| c.is_ascii_digit() != is_digits);
+        let (chunk_a, rest_a) = a.split_at(split(a).unwrap_or(a.len()));
+        let (chunk_b, rest_b) = b.split_at(split(b).unwrap_or(b.len()));
+        let ordering = if is_digits {
+            let value_a = chunk_a.trim_start_matches('0');
+            let value_b = chunk_b.trim_start_matches('0');
+            value_a
+                .len()
+                .cmp(&value_b.len())
+                .then_with(|| value_a.cmp(value_b))
+        } else {
+            chunk_a.cmp(chunk_b)
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+        a = rest_a;
+        b = rest_b;
+    }
+}
+
+/// A column to sort by, along with the direction and comparison to use.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SortKey<'a> {
+    pub column: Column<'a>,
+    pub order: Order,
+    pub compare: Compare,
+}
+
+impl<'a> SortKey<'a> {
+    /// Sort by a column in ascending lexical order.
+    pub fn new<C: Into<Column<'a>>>(column: C) -> SortKey<'a> {
+        SortKey {
+            column: column.into(),
+            order: Order::Ascending,
+            compare: Compare::Lexical,
+        }
+    }
+
+    /// Sort in descending order instead.
+    pub fn descending(mut self) -> SortKey<'a> {
+        self.order = Order::Descending;
+        self
+    }
+
+    /// Compare fields using the specified comparison.
+    pub fn by(mut self, compare: Compare) -> SortKey<'a> {
+        self.compare = compare;
+        self
+    }
+}
+
+/// A sort key whose column has been resolved to an index.
+struct ResolvedKey {
+    column: usize,
+    order: Order,
+    compare: Compare,
+}
+
+fn resolve(keys: &[SortKey], headers: Option<&[String]>, width: usize) -> Result<Vec<ResolvedKey>> {
+    keys.iter()
+        .map(|key| {
+            Ok(ResolvedKey {
+                column: key.column.resolve(headers, width)?,
+                order: key.order,
+                compare: key.compare,
+            })
+        })
+        .collect()
+}
+
+/// A field of a record being sorted.
+trait SortField {
+    fn text(&self) -> &str;
+}
+
+impl SortField for String {
+    fn text(&self) -> &str {
+        self
+    }
+}
+
+/// A null field sorts like an empty one.
+impl SortField for Option<String> {
+    fn text(&self) -> &str {
+        self.as_deref().unwrap_or_default()
+    }
+}
+
+fn compare_records<F: SortField>(keys: &[ResolvedKey], a: &[F], b: &[F]) -> Ordering {
+    for key in keys {
+        let ordering = key
+            .compare
+            .compare(a[key.column].text(), b[key.column].text());
+        let ordering = match key.order {
+            Order::Ascending => ordering,
+            Order::Descending => ordering.reverse(),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+impl Dsv {
+    /// Sort the records by one or more columns.
+    ///
+    /// Records are compared by each key in turn until they differ. The sort is
+    /// stable, so records that compare equal keep their relative order.
+    pub fn sort_by_columns(&mut self, keys: &[SortKey]) -> Result<()> {
+        let keys = resolve(keys, self.headers(), self.width())?;
+        let mut order: Vec<usize> = (0..self.records.len()).collect();
+        order.sort_by(|&a, &b| compare_records(&keys, &self.records[a], &self.records[b]));
+        self.reorder(&order);
+        Ok(())
+    }
+}
+
+/// Sort a stream of records that may be larger than memory.
+///
+/// Records are read in runs of at most `run_len` records, each of which is
+/// sorted in memory and written to a temporary file. The runs are then merged
+/// into the output. The sort is stable. If the options specify headers, they
+/// are written to the output first and can be used to name sort keys. Fields
+/// that match one of `Options::null_values` are written back as null, and sort
+/// like empty fields.
+#[cfg(feature = "std")]
+pub fn external_sort<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    keys: &[SortKey],
+    options: Options,
+    run_len: usize,
+) -> Result<()> {
+    let dir = std::env::temp_dir();
+    external_sort_in(&dir, reader, writer, keys, options, run_len)
+}
+
+/// Sort a stream of records as `external_sort` does, with runs written to the
+/// specified directory.
+#[cfg(feature = "std")]
+fn external_sort_in<R: Read, W: Write>(
+    dir: &Path,
+    reader: R,
+    writer: W,
+    keys: &[SortKey],
+    options: Options,
+    run_len: usize,
+) -> Result<()> {
+    let run_len = run_len.max(1);
+    // runs hold records only, but are written and read back with the same
+    // delimiter, quotes, and null values as the input
+    let run_options = Options {
+        projection: None,
+        headers: false,
+        skip_rows: 0,
+        ..options.clone()
+    };
+    let mut output = Writer::with_options(writer, options.clone());
+    let has_headers = options.headers;
+    let mut records = StreamReader::with_options(reader, options);
+
+    let headers = if has_headers {
+        records.read_record()?
+    } else {
+        None
+    };
+    if let Some(headers) = &headers {
+        output.write_record(headers)?;
+    }
+
+    let mut resolved = None;
+    let mut runs = vec![];
+    let mut run = Vec::with_capacity(run_len);
+    loop {
+        let record = records.read_record_opt()?;
+        let done = record.is_none();
+        if let Some(record) = record {
+            if resolved.is_none() {
+                resolved = Some(resolve(keys, headers.as_deref(), record.len())?);
+            }
+            run.push(record);
+            if run.len() < run_len {
+                continue;
+            }
+        }
+
+        let Some(keys) = &resolved else {
+            break;
+        };
+        run.sort_by(|a, b| compare_records(keys, a, b));
+        if done && runs.is_empty() {
+            // everything fit in a single run
+            for record in run.drain(..) {
+                output.write_record_opt(record)?;
+            }
+            break;
+        }
+        if !run.is_empty() {
+            runs.push(Run::write(dir, &mut run, &run_options)?);
+        }
+        if done {
+            merge(runs, keys, &run_options, &mut output)?;
+            break;
+        }
+    }
+
+    output.flush()
+}
+
+/// Merge sorted runs into the output, preferring earlier runs on ties.
+#[cfg(feature = "std")]
+fn merge<W: Write>(
+    runs: Vec<Run>,
+    keys: &[ResolvedKey],
+    options: &Options,
+    output: &mut Writer<W>,
+) -> Result<()> {
+    let mut readers = runs
+        .iter()
+        .map(|run| run.read(options))
+        .collect::<Result<Vec<_>>>()?;
+    let mut heads = readers
+        .iter_mut()
+        .map(StreamReader::read_record_opt)
+        .collect::<Result<Vec<_>>>()?;
+
+    loop {
+        let mut min: Option<usize> = None;
+        for (ix, head) in heads.iter().enumerate() {
+            let Some(head) = head else {
+                continue;
+            };
+            match min {
+                Some(m) if compare_records(keys, head, heads[m].as_ref().unwrap()).is_ge() => {}
+                _ => min = Some(ix),
+            }
+        }
+        let Some(ix) = min else {
+            return Ok(());
+        };
+        let next = readers[ix].read_record_opt()?;
+        let record = std::mem::replace(&mut heads[ix], next).unwrap();
+        output.write_record_opt(record)?;
+    }
+}
+
+/// The number of run files created by this process, used to name them.
+#[cfg(feature = "std")]
+static RUN_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A sorted run of records stored in a temporary file.
+#[cfg(feature = "std")]
+struct Run {
+    path: PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl Run {
+    /// Write the records to a new file in a directory, leaving the buffer
+    /// empty.
+    fn write(dir: &Path, records: &mut Vec<Vec<Option<String>>>, options: &Options) -> Result<Run> {
+        let (file, run) = Run::create(dir)?;
+        let mut writer = Writer::with_options(BufWriter::new(file), options.clone());
+        for record in records.drain(..) {
+            writer.write_record_opt(record)?;
+        }
+        writer.flush()?;
+        Ok(run)
+    }
+
+    /// Create a file in a directory with a name that is not already taken,
+    /// such as by a file left behind by an earlier process with the same id.
+    fn create(dir: &Path) -> Result<(File, Run)> {
+        loop {
+            let count = RUN_COUNT.fetch_add(1, AtomicOrdering::Relaxed);
+            let name = format!("dsv-sort-{}-{}.csv", std::process::id(), count);
+            let path = dir.join(name);
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok((file, Run { path })),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    fn read(&self, options: &Options) -> Result<StreamReader<BufReader<File>>> {
+        let file = BufReader::new(File::open(&self.path)?);
+        Ok(StreamReader::with_options(file, options.clone()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DsvBuilder, Error};
+
+    #[test]
+    fn natural() {
+        let mut values = ["file10", "file2", "file1", "file02b", "file", "a"];
+        values.sort_by(|a, b| compare_natural(a, b));
+        assert_eq!(values, ["a", "file", "file1", "file2", "file02b", "file10"]);
+    }
+
+    #[test]
+    fn numeric() {
+        let mut values = ["10", "x", "9.5", "-1", "abc", "1e3"];
+        values.sort_by(|a, b| compare_numeric(a, b));
+        assert_eq!(values, ["-1", "9.5", "10", "1e3", "abc", "x"]);
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let ordering = Compare::CaseInsensitive.compare("apple", "Banana");
+        assert_eq!(ordering, Ordering::Less);
+        assert_eq!(
+            Compare::Lexical.compare("apple", "Banana"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn multiple_keys() -> Result<()> {
+        let mut dsv = DsvBuilder::new()
+            .headers(["country", "price", "id"])
+            .record(["DE", "10", "1"])
+            .record(["FR", "9", "2"])
+            .record(["DE", "9", "3"])
+            .record(["DE", "10", "4"])
+            .build()?;
+        let keys = [
+            SortKey::new("country"),
+            SortKey::new(1).descending().by(Compare::Numeric),
+        ];
+        dsv.sort_by_columns(&keys)?;
+        let ids: Vec<_> = dsv.column(2).unwrap().collect();
+        assert_eq!(ids, ["1", "4", "3", "2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_column() {
+        let mut dsv = Dsv::from_str("a,b").unwrap();
+        let result = dsv.sort_by_columns(&[SortKey::new("a")]);
+        assert!(matches!(result, Err(Error::UnknownColumn(name)) if name == "a"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn external() -> Result<()> {
+        let input = "id,value\n1,c\n2,a\n3,b\n4,a\n5,c\n6,b\n7,a\n";
+        let options = Options {
+            headers: true,
+            ..Default::default()
+        };
+        let mut output = vec![];
+        external_sort(
+            input.as_bytes(),
+            &mut output,
+            &[SortKey::new("value")],
+            options,
+            2,
+        )?;
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, "id,value\n2,a\n4,a\n7,a\n3,b\n6,b\n1,c\n5,c\n");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn external_single_run() -> Result<()> {
+        let mut output = vec![];
+        let keys = [SortKey::new(0).descending()];
+        external_sort(&b"a\nc\nb"[..], &mut output, &keys, Options::default(), 10)?;
+        assert_eq!(output, b"c\nb\na\n");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn external_options() -> Result<()> {
+        let input = "# title\nid;name\n1;\"b\n2;a,c\n3;a\n";
+        let options = Options {
+            delimiter: b';',
+            quotes: crate::Quotes::Insignificant,
+            headers: true,
+            skip_rows: 1,
+            ..Default::default()
+        };
+        let mut output = vec![];
+        external_sort(
+            input.as_bytes(),
+            &mut output,
+            &[SortKey::new("name")],
+            options,
+            1,
+        )?;
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, "id;name\n1;\"b\n3;a\n2;a,c\n");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn external_nulls() -> Result<()> {
+        let input = "id,value\n1,\\N\n2,\"\\N\"\n3,a\n4,\\N\n5,\n";
+        let options = Options {
+            headers: true,
+            null_values: vec!["\\N".into()],
+            ..Default::default()
+        };
+        for run_len in [1, 2, 10] {
+            let mut output = vec![];
+            let keys = [SortKey::new("value"), SortKey::new("id").descending()];
+            external_sort(
+                input.as_bytes(),
+                &mut output,
+                &keys,
+                options.clone(),
+                run_len,
+            )?;
+            let output = String::from_utf8(output).unwrap();
+            assert_eq!(
+                output, "id,value\n5,\n4,\\N\n1,\\N\n2,\"\\N\"\n3,a\n",
+                "runs of {run_len}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn external_leftover_runs() -> Result<()> {
+        // files left behind with the names of the next runs are skipped, in a
+        // directory of this test's own so that other sorts can not take them
+        let dir = std::env::temp_dir().join(format!("dsv-sort-test-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let start = RUN_COUNT.load(AtomicOrdering::Relaxed);
+        let leftovers: Vec<_> = (start..start + 64)
+            .map(|count| dir.join(format!("dsv-sort-{}-{}.csv", std::process::id(), count)))
+            .collect();
+        for path in &leftovers {
+            fs::write(path, "leftover")?;
+        }
+        let mut output = vec![];
+        let result = external_sort_in(
+            &dir,
+            &b"c\na\nb"[..],
+            &mut output,
+            &[SortKey::new(0)],
+            Options::default(),
+            1,
+        );
+        let kept = leftovers
+            .iter()
+            .all(|path| fs::read_to_string(path).is_ok_and(|text| text == "leftover"));
+        fs::remove_dir_all(&dir)?;
+        result?;
+        assert!(kept);
+        assert_eq!(output, b"a\nb\nc\n");
+        Ok(())
+    }
+}
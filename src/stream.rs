@@ -0,0 +1,171 @@
+use std::io::{self, Read};
+
+use crate::{
+    decoder::{DecodeFn, Decoded, Decoder, FinishFn},
+    error::Result,
+    Options,
+};
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// A streaming reader over delimiter-separated values.
+///
+/// A `StreamReader` parses records incrementally from any source implementing
+/// `Read`, such as a file or a socket, without holding the whole input in
+/// memory. Fields are copied out of the input.
+pub struct StreamReader<R> {
+    reader: R,
+    decoder: Decoder,
+    buffer: Box<[u8]>,
+    pos: usize,
+    len: usize,
+    eof: bool,
+    done: bool,
+}
+
+impl<R: Read> StreamReader<R> {
+    /// Create a streaming reader over a source.
+    pub fn new(reader: R) -> StreamReader<R> {
+        StreamReader::with_options(reader, Options::default())
+    }
+
+    /// Create a streaming reader over a source with options.
+    pub fn with_options(reader: R, options: Options) -> StreamReader<R> {
+        StreamReader {
+            reader,
+            decoder: Decoder::with_options(options),
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Read the next record, returning `None` at the end of the input.
+    pub fn read_record(&mut self) -> Result<Option<Vec<String>>> {
+        self.read_with(Decoder::decode, Decoder::finish)
+    }
+
+    /// Read the next record, with `None` in place of fields that match one of
+    /// `Options::null_values`. Returns `None` at the end of the input.
+    pub fn read_record_opt(&mut self) -> Result<Option<Vec<Option<String>>>> {
+        self.read_with(Decoder::decode_opt, Decoder::finish_opt)
+    }
+
+    fn read_with<T>(&mut self, decode: DecodeFn<T>, finish: FinishFn<T>) -> Result<Option<T>> {
+        loop {
+            if self.pos == self.len {
+                if self.eof {
+                    return finish(&mut self.decoder);
+                }
+                match self.reader.read(&mut self.buffer) {
+                    Ok(0) => self.eof = true,
+                    Ok(len) => self.len = len,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e.into()),
+                }
+                self.pos = 0;
+                if self.eof {
+                    self.len = 0;
+                }
+                continue;
+            }
+            let (consumed, decoded) = decode(&mut self.decoder, &self.buffer[self.pos..self.len])?;
+            self.pos += consumed;
+            if let Decoded::Record(record) = decoded {
+                return Ok(Some(record));
+            }
+        }
+    }
+
+    /// Consume the reader, returning the underlying source.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for StreamReader<R> {
+    type Item = Result<Vec<String>>;
+
+    /// Returns the next record in the input.
+    ///
+    /// The reader stops after the first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A source that returns at most one byte per read.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.0.len().min(buf.len()).min(1);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn records() -> Result<()> {
+        let text = "foo,\"bar\nbaz\"\nqux,bux";
+        let reader = StreamReader::new(Trickle(text.as_bytes()));
+        let records: Vec<_> = reader.collect::<Result<_>>()?;
+        assert_eq!(records, vec![vec!["foo", "bar\nbaz"], vec!["qux", "bux"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn split_multibyte_char() -> Result<()> {
+        let text = "34°03′N,118°15′W";
+        let reader = StreamReader::new(Trickle(text.as_bytes()));
+        let records: Vec<_> = reader.collect::<Result<_>>()?;
+        assert_eq!(records, vec![vec!["34°03′N", "118°15′W"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn null_values() -> Result<()> {
+        let options = Options {
+            null_values: vec!["".into(), "NA".into()],
+            ..Default::default()
+        };
+        let text = "a,,NA\n\"NA\",\"\",b";
+        let mut reader = StreamReader::with_options(Trickle(text.as_bytes()), options);
+        let first = reader.read_record_opt()?.unwrap();
+        assert_eq!(first, [Some("a".into()), None, None]);
+        let second = reader.read_record_opt()?.unwrap();
+        assert_eq!(
+            second,
+            [Some("NA".into()), Some("".into()), Some("b".into())]
+        );
+        assert_eq!(reader.read_record_opt()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut reader = StreamReader::new(&b"\xff,foo"[..]);
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+}
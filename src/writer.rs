@@ -0,0 +1,406 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::{
+    error::{Error, Result},
+    parser::Quotes,
+    Dsv, Options, StreamReader,
+};
+
+/// Formats records as delimiter-separated values.
+///
+/// The encoder is shared by the writers so that they produce identical output.
+pub(crate) struct Encoder {
+    delimiter: u8,
+    quotes: Quotes,
+    null_values: Vec<String>,
+    num_expected_fields: Option<usize>,
+}
+
+impl Encoder {
+    pub fn new(options: Options) -> Self {
+        Self {
+            delimiter: options.delimiter,
+            quotes: options.quotes,
+            null_values: options.null_values,
+            num_expected_fields: None,
+        }
+    }
+
+    /// Append a record, including its terminating newline, to the output.
+    pub fn encode<I, T>(&mut self, record: I, output: &mut Vec<u8>) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.encode_opt(record.into_iter().map(Some), output)
+    }
+
+    /// Append a record in which `None` marks a null field.
+    ///
+    /// Null fields are written as the first of the null values, or left empty
+    /// if there are none. Fields that match a null value are quoted.
+    pub fn encode_opt<I, T>(&mut self, record: I, output: &mut Vec<u8>) -> Result<()>
+    where
+        I: IntoIterator<Item = Option<T>>,
+        T: AsRef<str>,
+    {
+        let start = output.len();
+        let mut len = 0;
+        let mut null = false;
+        for field in record {
+            if len > 0 {
+                output.push(self.delimiter);
+            }
+            null = field.is_none();
+            let result = match field {
+                Some(field) => self.encode_field(field.as_ref(), output),
+                None => {
+                    let null = self.null_values.first().map_or("", String::as_str);
+                    output.extend_from_slice(null.as_bytes());
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                output.truncate(start);
+                return Err(e);
+            }
+            len += 1;
+        }
+
+        // a record with one empty field would otherwise be an empty line
+        if len == 1 && output.len() == start {
+            match self.quotes {
+                Quotes::Significant if !null => output.extend_from_slice(br#""""#),
+                _ => return Err(Error::Unrepresentable),
+            }
+        }
+
+        match self.num_expected_fields {
+            None => self.num_expected_fields = Some(len),
+            Some(expected) if len != expected => {
+                output.truncate(start);
+                return Err(Error::FieldCount {
+                    expected,
+                    found: len,
+                });
+            }
+            _ => {}
+        }
+
+        output.push(b'\n');
+        Ok(())
+    }
+
+    fn encode_field(&self, field: &str, output: &mut Vec<u8>) -> Result<()> {
+        // a value that would otherwise be read back as null
+        let is_null_value = self.null_values.iter().any(|null| null == field);
+        let needs_quotes = is_null_value
+            || field
+                .bytes()
+                .any(|b| b == self.delimiter || b == b'\n' || b == b'\r' || b == b'"');
+        if !needs_quotes {
+            output.extend_from_slice(field.as_bytes());
+            return Ok(());
+        }
+
+        match self.quotes {
+            Quotes::Significant => {
+                output.push(b'"');
+                output.extend_from_slice(field.replace('"', r#""""#).as_bytes());
+                output.push(b'"');
+            }
+            Quotes::Insignificant
+                if is_null_value || field.bytes().any(|b| b == self.delimiter || b == b'\n') =>
+            {
+                return Err(Error::Unrepresentable)
+            }
+            Quotes::Insignificant => output.extend_from_slice(field.as_bytes()),
+        }
+        Ok(())
+    }
+}
+
+/// A writer of delimiter-separated values.
+///
+/// Fields are quoted only when necessary. Each record must contain the same
+/// number of fields as the first record written.
+///
+/// A writer created in append mode writes the headers of a table only once,
+/// so that several tables can be combined into one output.
+pub struct Writer<W> {
+    writer: W,
+    encoder: Encoder,
+    buffer: Vec<u8>,
+    append: bool,
+    /// The headers already written, in append mode.
+    headers: Option<Vec<String>>,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a writer over a destination.
+    pub fn new(writer: W) -> Writer<W> {
+        Writer::with_options(writer, Options::default())
+    }
+
+    /// Create a writer over a destination with options.
+    pub fn with_options(writer: W, options: Options) -> Writer<W> {
+        Writer {
+            writer,
+            encoder: Encoder::new(options),
+            buffer: vec![],
+            append: false,
+            headers: None,
+        }
+    }
+
+    /// Create a writer in append mode over a destination with options.
+    pub fn append(writer: W, options: Options) -> Writer<W> {
+        Writer {
+            append: true,
+            ..Writer::with_options(writer, options)
+        }
+    }
+
+    /// Write a record.
+    pub fn write_record<I, T>(&mut self, record: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.buffer.clear();
+        self.encoder.encode(record, &mut self.buffer)?;
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    /// Write a record in which `None` marks a null field.
+    ///
+    /// Null fields are written as the first of `Options::null_values`, or left
+    /// empty if there are none. Fields that match a null value are quoted so
+    /// that they are not read back as null.
+    pub fn write_record_opt<I, T>(&mut self, record: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Option<T>>,
+        T: AsRef<str>,
+    {
+        self.buffer.clear();
+        self.encoder.encode_opt(record, &mut self.buffer)?;
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    /// Write the headers, if any, and every record in a table.
+    ///
+    /// In append mode, headers that have already been written are skipped,
+    /// and headers that differ from them are rejected with `Error::Headers`.
+    pub fn write_dsv(&mut self, dsv: &Dsv) -> Result<()> {
+        match (dsv.headers(), &self.headers) {
+            (Some(headers), Some(written)) if self.append => {
+                if headers != written.as_slice() {
+                    return Err(Error::Headers);
+                }
+            }
+            (Some(headers), _) => {
+                self.write_record(headers)?;
+                if self.append {
+                    self.headers = Some(headers.to_vec());
+                }
+            }
+            (None, _) => {}
+        }
+        for record in dsv.iter() {
+            self.write_record_opt(record.fields_opt())?;
+        }
+        Ok(())
+    }
+
+    /// Flush the underlying destination.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Consume the writer, returning the underlying destination.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl Writer<File> {
+    /// Open the file at the specified path in append mode, creating it if it
+    /// does not exist.
+    ///
+    /// If the file already contains records, new records must have the same
+    /// number of fields. If the options specify headers, the first record of
+    /// the file is treated as headers that have already been written.
+    pub fn append_path<P: AsRef<Path>>(path: P, options: Options) -> Result<Writer<File>> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        let has_headers = options.headers;
+        let first = StreamReader::with_options(&file, options.clone())
+            .next()
+            .transpose()?;
+
+        // make sure new records start on a line of their own
+        if first.is_some() {
+            let mut last = [0];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n")?;
+            }
+        }
+
+        let mut writer = Writer::append(file, options);
+        if let Some(first) = first {
+            writer.encoder.num_expected_fields = Some(first.len());
+            if has_headers {
+                writer.headers = Some(first);
+            }
+        }
+        Ok(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(records: &[&[&str]], options: Options) -> Result<String> {
+        let mut writer = Writer::with_options(vec![], options);
+        for record in records {
+            writer.write_record(record.iter())?;
+        }
+        Ok(String::from_utf8(writer.into_inner()).unwrap())
+    }
+
+    #[test]
+    fn plain() -> Result<()> {
+        let text = write(&[&["foo", "bar"], &["baz", ""]], Options::default())?;
+        assert_eq!(text, "foo,bar\nbaz,\n");
+        Ok(())
+    }
+
+    #[test]
+    fn quoted() -> Result<()> {
+        let text = write(&[&["a,b", "c\nd", "e\"f"]], Options::default())?;
+        assert_eq!(text, "\"a,b\",\"c\nd\",\"e\"\"f\"\n");
+        Ok(())
+    }
+
+    #[test]
+    fn single_empty_field() -> Result<()> {
+        let text = write(&[&[""], &["foo"]], Options::default())?;
+        assert_eq!(text, "\"\"\nfoo\n");
+        assert_eq!(Dsv::from_str(&text)?.records(), &[vec![""], vec!["foo"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let text = "1997,Ford,E350,\"Super, \"\"luxurious\"\" truck\"\n";
+        let dsv = Dsv::from_str(text)?;
+        let mut writer = Writer::new(vec![]);
+        writer.write_dsv(&dsv)?;
+        assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), text);
+        Ok(())
+    }
+
+    #[test]
+    fn null_values() -> Result<()> {
+        let options = Options {
+            null_values: vec!["\\N".into(), "".into()],
+            ..Default::default()
+        };
+        let mut writer = Writer::with_options(vec![], options.clone());
+        writer.write_record_opt([Some("a"), None, Some(""), Some("\\N")])?;
+        let text = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(text, "a,\\N,\"\",\"\\N\"\n");
+
+        let text = "a,,\"\",\\N\n";
+        let dsv = Dsv::from_str_with_options(text, options.clone())?;
+        let mut writer = Writer::with_options(vec![], options.clone());
+        writer.write_dsv(&dsv)?;
+        let written = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(written, "a,\\N,\"\",\\N\n");
+        assert_eq!(Dsv::from_str_with_options(&written, options)?, dsv);
+
+        let mut writer = Writer::new(vec![]);
+        let result = writer.write_record_opt([None::<&str>]);
+        assert!(matches!(result, Err(Error::Unrepresentable)));
+        Ok(())
+    }
+
+    #[test]
+    fn mismatched_field_count() {
+        let result = write(&[&["foo", "bar"], &["baz"]], Options::default());
+        assert!(matches!(
+            result,
+            Err(Error::FieldCount {
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn insignificant_quotes() {
+        let options = Options {
+            quotes: Quotes::Insignificant,
+            ..Default::default()
+        };
+        assert_eq!(write(&[&["\"a\""]], options.clone()).unwrap(), "\"a\"\n");
+        assert!(matches!(
+            write(&[&["a,b"]], options),
+            Err(Error::Unrepresentable)
+        ));
+    }
+
+    #[test]
+    fn append_skips_headers() -> Result<()> {
+        let options = Options {
+            headers: true,
+            ..Default::default()
+        };
+        let january = Dsv::from_str_with_options("fruit,count\napples,2", options.clone())?;
+        let february = Dsv::from_str_with_options("fruit,count\noranges,3", options.clone())?;
+        let mut writer = Writer::append(vec![], options.clone());
+        writer.write_dsv(&january)?;
+        writer.write_dsv(&february)?;
+
+        let other = Dsv::from_str_with_options("count,fruit\n4,pears", options)?;
+        assert!(matches!(writer.write_dsv(&other), Err(Error::Headers)));
+        let text = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(text, "fruit,count\napples,2\noranges,3\n");
+        Ok(())
+    }
+
+    #[test]
+    fn append_path() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("dsv-append-{}.csv", std::process::id()));
+        std::fs::write(&path, "fruit,count\napples,2")?;
+        let options = Options {
+            headers: true,
+            ..Default::default()
+        };
+        let dsv = Dsv::from_str_with_options("fruit,count\noranges,3", options.clone())?;
+        let mut writer = Writer::append_path(&path, options)?;
+        writer.write_dsv(&dsv)?;
+        let result = writer.write_record(["pears"]);
+        writer.flush()?;
+        drop(writer);
+
+        let text = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(&path)?;
+        assert!(matches!(result, Err(Error::FieldCount { .. })));
+        assert_eq!(text, "fruit,count\napples,2\noranges,3\n");
+        Ok(())
+    }
+}